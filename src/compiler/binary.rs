@@ -0,0 +1,488 @@
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+use crate::display::display_state;
+use crate::presets::UniverseMetadata;
+use crate::universe::machine::{Action, State, Write};
+use crate::universe::tape::{Edge, Tape, TapeMode};
+use crate::universe::{Symbol, Universe};
+
+use super::Builder;
+
+const ONE: Symbol = Symbol::new(1);
+
+/// How the symbols of a machine are written as blocks of empty symbols and ones.
+///
+/// Every symbol is numbered, with the empty symbol as 0, and written in binary as a block of fixed width, most
+/// significant bit first.
+/// A cell at position p is written as the block starting at position (p - 1) * width + 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockEncoding {
+    /// Symbol of each number
+    pub symbols: Vec<Symbol>,
+    pub width: usize,
+}
+
+impl BlockEncoding {
+    /// Encoding of the symbols of the machine, those written by its transition function, and those on its tape.
+    pub fn new(universe_meta: &UniverseMetadata) -> Self {
+        let added = universe_meta.transition_function_buidler.added();
+        let written = added.iter().flat_map(|(input, output)| match output.write {
+            Write::Print(symbol) => vec![input.symbol, symbol],
+            _ => vec![input.symbol],
+        });
+
+        let mut symbols = vec![Symbol::empty()];
+        let all = universe_meta
+            .symbol_set
+            .iter()
+            .cloned()
+            .chain(written)
+            .chain(universe_meta.universe.tape.all_symbols());
+        for symbol in all {
+            if !symbols.contains(&symbol) {
+                symbols.push(symbol);
+            }
+        }
+
+        let mut width = 1;
+        while 1 << width < symbols.len() {
+            width += 1;
+        }
+        BlockEncoding { symbols, width }
+    }
+
+    /// Position of the first cell of the block which encodes the given position.
+    pub fn encode_head(&self, pos: isize) -> isize {
+        (pos - 1) * self.width as isize + 1
+    }
+
+    /// Position which is encoded by the block the given position is in.
+    pub fn decode_head(&self, pos: isize) -> isize {
+        (pos - 1).div_euclid(self.width as isize) + 1
+    }
+
+    pub fn encode_tape(&self, tape: &Tape) -> Tape {
+        let mut encoded = Tape::default();
+        for pos in written(tape) {
+            let start = self.encode_head(pos);
+            for (i, bit) in self.bits(tape.read(pos)).into_iter().enumerate() {
+                if bit {
+                    encoded.write(Write::from(ONE), start + i as isize);
+                }
+            }
+        }
+        encoded
+    }
+
+    pub fn decode_tape(&self, tape: &Tape) -> Result<Tape, String> {
+        let (Some(leftmost), Some(rightmost)) = (tape.leftmost_written(), tape.rightmost_written())
+        else {
+            return Ok(Tape::default());
+        };
+
+        let mut decoded = Tape::default();
+        for pos in self.decode_head(leftmost)..=self.decode_head(rightmost) {
+            let start = self.encode_head(pos);
+            let code = tape
+                .symbols(start..start + self.width as isize)
+                .into_iter()
+                .try_fold(0, |code, bit| match bit {
+                    bit if bit.is_empty() => Ok(code * 2),
+                    ONE => Ok(code * 2 + 1),
+                    other => Err(format!("not a bit: {other}")),
+                })?;
+            let symbol = self
+                .symbols
+                .get(code)
+                .ok_or_else(|| format!("unknown symbol number: {code}"))?;
+
+            decoded.write(Write::from(*symbol), pos);
+        }
+        Ok(decoded)
+    }
+
+    fn bits(&self, symbol: Symbol) -> Vec<bool> {
+        let code = self.symbols.iter().position(|s| *s == symbol).unwrap_or(0);
+        (0..self.width).rev().map(|i| code >> i & 1 == 1).collect()
+    }
+
+    fn code(&self, bits: &[bool]) -> usize {
+        bits.iter().fold(0, |code, bit| code * 2 + *bit as usize)
+    }
+}
+
+/// Positions of the cells which were written.
+fn written(tape: &Tape) -> RangeInclusive<isize> {
+    match tape.leftmost_written().zip(tape.rightmost_written()) {
+        Some((leftmost, rightmost)) => leftmost..=rightmost,
+        None => RangeInclusive::new(1, 0),
+    }
+}
+
+/// Equivalent machine over only the empty symbol and 1, with its tape encoded using the given encoding.
+///
+/// The machine reads a block from left to right, then writes the new block from right to left, and moves to the
+/// next block if needed.
+/// Its states are named after the state they simulate, followed by what they are doing: `A/01` has read 01 so far,
+/// `A/01/write0` writes the first bit after reading 01, and `A/right2` moves two more cells to the right before
+/// continuing in A.
+/// Erasing a symbol writes the empty symbol instead, while machines which delete cells can not be reduced.
+/// The ends of a bounded, circular or one-way tape move to the ends of their blocks, where a head which stays at an end
+/// can not be reduced, as it would stay in the middle of a block.
+pub fn reduce(
+    universe_meta: &UniverseMetadata,
+    encoding: &BlockEncoding,
+) -> Result<UniverseMetadata, String> {
+    let first = |pos: isize| encoding.encode_head(pos);
+    let last = |pos: isize| encoding.encode_head(pos + 1) - 1;
+    let tape_mode = match universe_meta.universe.tape_mode {
+        TapeMode::Bounded {
+            edge: Edge::Stay, ..
+        }
+        | TapeMode::OneWay {
+            edge: Edge::Stay, ..
+        } => {
+            return Err(String::from(
+                "a tape where the head stays at its end can not be reduced to 2 symbols",
+            ))
+        }
+        TapeMode::Unbounded => TapeMode::Unbounded,
+        TapeMode::Bounded { left, right, edge } => TapeMode::Bounded {
+            left: first(left),
+            right: last(right),
+            edge,
+        },
+        TapeMode::Circular { left, right } => TapeMode::Circular {
+            left: first(left),
+            right: last(right),
+        },
+        TapeMode::OneWay { left, edge } => TapeMode::OneWay {
+            left: first(left),
+            edge,
+        },
+    };
+
+    let name = |state: State| display_state(state, &universe_meta.display_state_as);
+    let read = |state: State, bits: &[bool]| {
+        let bits: String = bits
+            .iter()
+            .map(|bit| if *bit { '1' } else { '0' })
+            .collect();
+        format!("{}/{bits}", name(state))
+    };
+    let bit = |bit: bool| Write::from(if bit { ONE } else { Symbol::empty() });
+    let width = encoding.width;
+
+    let mut builder = Builder::default();
+    // states which move to the start of a block are shared by every transition moving to the same state
+    let mut moving_states = HashSet::new();
+    let transitions = universe_meta.transition_function_buidler.build();
+    let mut states = universe_meta.state_set.clone();
+    for (input, _) in universe_meta.transition_function_buidler.added() {
+        if !states.contains(&input.state) {
+            states.push(input.state);
+        }
+    }
+
+    for state in states {
+        // read every prefix of a block, as a binary tree
+        for len in 0..width {
+            for prefix in 0..1_usize << len {
+                let prefix: Vec<bool> = (0..len).rev().map(|i| prefix >> i & 1 == 1).collect();
+                for (value, symbol) in [(false, Symbol::empty()), (true, ONE)] {
+                    let bits: Vec<bool> = prefix.iter().cloned().chain([value]).collect();
+                    if bits.len() < width {
+                        builder.add(
+                            &read(state, &prefix),
+                            symbol,
+                            Write::None,
+                            Action::R,
+                            &read(state, &bits),
+                        );
+                        continue;
+                    }
+
+                    let Some(scanned) = encoding.symbols.get(encoding.code(&bits)) else {
+                        continue;
+                    };
+                    let Ok(output) = transitions.act(state, *scanned) else {
+                        continue;
+                    };
+
+                    let print = match output.write {
+                        Write::Print(symbol) => symbol,
+                        Write::Erase => Symbol::empty(),
+                        Write::None => *scanned,
+                        Write::Delete => {
+                            return Err(format!(
+                                "({}, {scanned}) deletes a cell, which a block encoding can not do",
+                                name(state)
+                            ))
+                        }
+                    };
+                    let print = encoding.bits(print);
+                    let next = name(output.state);
+                    let moving = |action: Action, remaining: usize| match (action, remaining) {
+                        _ if output.state.is_halted()
+                            && (remaining == 0 || action == Action::N) =>
+                        {
+                            next.clone()
+                        }
+                        (_, 0) | (Action::N, _) => read(output.state, &[]),
+                        (Action::L, remaining) => format!("{next}/left{remaining}"),
+                        (Action::R, remaining) => format!("{next}/right{remaining}"),
+                    };
+
+                    // write from right to left, ending at the start of the block
+                    let writing = |i: usize| format!("{}/write{i}", read(state, &bits));
+                    let from = read(state, &prefix);
+                    let to = match width {
+                        1 => moving(output.action, 0),
+                        _ => writing(width - 2),
+                    };
+                    let action = if width == 1 { output.action } else { Action::L };
+                    builder.add(&from, symbol, bit(print[width - 1]), action, &to);
+
+                    for i in (0..width.saturating_sub(1)).rev() {
+                        let (action, to) = match i {
+                            0 => (output.action, moving(output.action, width - 1)),
+                            i => (Action::L, writing(i - 1)),
+                        };
+                        for symbol in [Symbol::empty(), ONE] {
+                            builder.add(&writing(i), symbol, bit(print[i]), action, &to);
+                        }
+                    }
+
+                    // move to the start of the next block
+                    for remaining in 1..width {
+                        if output.action == Action::N {
+                            break;
+                        }
+                        let from = moving(output.action, remaining);
+                        if !moving_states.insert(from.clone()) {
+                            continue;
+                        }
+                        for symbol in [Symbol::empty(), ONE] {
+                            let to = moving(output.action, remaining - 1);
+                            builder.add(&from, symbol, Write::None, output.action, &to);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let universe = &universe_meta.universe;
+    let initial_state = builder.state(&read(universe.machine.state, &[]));
+    let initial_pos = encoding.encode_head(universe.pos);
+    let mut reduced = Universe::new(vec![], 0, initial_state, builder.builder.build());
+    reduced.tape = encoding.encode_tape(&universe.tape);
+    reduced.pos = initial_pos;
+    reduced.tape_mode = tape_mode;
+
+    Ok(UniverseMetadata {
+        name: format!("{} over 2 symbols", universe_meta.name),
+        head_offset_hint: initial_pos.max(0) as usize,
+        symbol_set: vec![ONE],
+        state_set: builder.state_set,
+        display_state_as: builder.display_state_as,
+        display_symbol_as: Default::default(),
+        transition_function_buidler: builder.builder,
+        universe: reduced,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::presets::arithmetic;
+    use crate::presets::busy_beaver::{
+        four_state_busy_beaver, three_state_busy_beaver, two_state_busy_beaver,
+    };
+    use crate::presets::counter::{counter, decrementer};
+    use crate::presets::recognizer::palindrome;
+    use crate::presets::registry::presets;
+    use crate::presets::UniverseMetadata;
+    use crate::universe::check::Issue;
+    use crate::universe::tape::{Edge, Tape, TapeMode};
+    use crate::universe::{Symbol, Universe};
+
+    use super::{reduce, BlockEncoding};
+
+    fn run(mut universe: Universe, max_ticks: usize) -> Universe {
+        while !universe.machine.state.is_halted() && universe.ticks < max_ticks {
+            universe.tick().unwrap();
+        }
+        universe
+    }
+
+    /// Tape without empty symbols at its ends, and the head relative to the first symbol which is not empty.
+    fn trimmed(tape: &Tape, pos: isize) -> (String, isize) {
+        let symbols = tape.symbols(-1000..1000);
+        let first = symbols.iter().position(|s| !s.is_empty()).unwrap_or(0);
+        let last = symbols
+            .iter()
+            .rposition(|s| !s.is_empty())
+            .map_or(0, |i| i + 1);
+        let trimmed: Tape = symbols[first..last.max(first)].iter().cloned().collect();
+        (trimmed.to_string(), pos + 1000 - first as isize)
+    }
+
+    fn assert_same_result(universe_meta: UniverseMetadata, max_ticks: usize) {
+        let encoding = BlockEncoding::new(&universe_meta);
+        let reduced = reduce(&universe_meta, &encoding).unwrap();
+        let expected = run(universe_meta.universe, max_ticks);
+        let actual = run(reduced.universe, max_ticks * (4 * encoding.width + 2));
+
+        assert_eq!(
+            actual.machine.state.is_halted(),
+            expected.machine.state.is_halted()
+        );
+        assert!(expected.machine.state.is_halted(), "did not halt");
+        let decoded = encoding.decode_tape(&actual.tape).unwrap();
+        assert_eq!(
+            trimmed(&decoded, encoding.decode_head(actual.pos)),
+            trimmed(&expected.tape, expected.pos)
+        );
+        assert_eq!(
+            actual.pos,
+            encoding.encode_head(encoding.decode_head(actual.pos))
+        );
+    }
+
+    #[test_case(2, 1)]
+    #[test_case(3, 2)]
+    #[test_case(4, 2)]
+    #[test_case(5, 3)]
+    #[test_case(8, 3)]
+    #[test_case(9, 4)]
+    fn width(symbols: usize, expected: usize) {
+        let universe_meta = UniverseMetadata {
+            symbol_set: (1..symbols).map(Symbol::from).collect(),
+            ..two_state_busy_beaver()
+        };
+
+        assert_eq!(BlockEncoding::new(&universe_meta).width, expected);
+    }
+
+    #[test]
+    fn encode_decode_tape() {
+        let universe_meta = counter(10).with_input("907").unwrap();
+        let encoding = BlockEncoding::new(&universe_meta);
+        let tape = &universe_meta.universe.tape;
+
+        let encoded = encoding.encode_tape(tape);
+        assert!(encoded
+            .all_symbols()
+            .iter()
+            .all(|s| s.is_empty() || *s == Symbol::from(1)));
+        assert_eq!(&encoding.decode_tape(&encoded).unwrap(), tape);
+    }
+
+    #[test]
+    fn reduced_has_two_symbols() {
+        let reduced = reduce(&counter(10), &BlockEncoding::new(&counter(10))).unwrap();
+
+        for (input, output) in reduced.transition_function_buidler.added() {
+            assert!(input.symbol.is_empty() || input.symbol == Symbol::from(1));
+            assert!(match output.write {
+                crate::universe::machine::Write::Print(symbol) =>
+                    symbol.is_empty() || symbol == Symbol::from(1),
+                _ => true,
+            });
+        }
+    }
+
+    #[test]
+    fn presets_reduce_without_errors() {
+        for preset in presets() {
+            let universe_meta = (preset.build)();
+            let Ok(reduced) = reduce(&universe_meta, &BlockEncoding::new(&universe_meta)) else {
+                continue;
+            };
+            let issues = reduced.transition_function_buidler.check(
+                &reduced.symbol_set,
+                &reduced.state_set,
+                reduced.universe.machine.state,
+            );
+
+            let errors: Vec<_> = issues.into_iter().filter(Issue::is_error).collect();
+            assert_eq!(errors, vec![], "{}", preset.name);
+        }
+    }
+
+    #[test]
+    fn busy_beavers() {
+        assert_same_result(two_state_busy_beaver(), 100);
+        assert_same_result(three_state_busy_beaver(), 100);
+        assert_same_result(four_state_busy_beaver(), 1_000);
+    }
+
+    #[test_case("9")]
+    #[test_case("120")]
+    #[test_case("307")]
+    fn decrementer_decimal(input: &str) {
+        assert_same_result(decrementer(10).with_input(input).unwrap(), 100_000);
+    }
+
+    #[test_case("1121")]
+    #[test_case("101102111")]
+    fn binary_addition(input: &str) {
+        assert_same_result(
+            arithmetic::binary_addition().with_input(input).unwrap(),
+            10_000,
+        );
+    }
+
+    #[test_case("abba")]
+    #[test_case("aab")]
+    fn palindromes(input: &str) {
+        assert_same_result(palindrome().universe_meta.with_input(input).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn bounded_tape_is_scaled() {
+        let tape_mode = TapeMode::Bounded {
+            left: 0,
+            right: 5,
+            edge: Edge::Error,
+        };
+        let palindrome = palindrome().universe_meta.with_input("abba").unwrap();
+        let palindrome = palindrome.with_tape_mode(tape_mode).unwrap();
+        let encoding = BlockEncoding::new(&palindrome);
+        let reduced = reduce(&palindrome, &encoding).unwrap();
+
+        assert_eq!(encoding.width, 2);
+        assert_eq!(
+            reduced.universe.tape_mode,
+            TapeMode::Bounded {
+                left: -1,
+                right: 10,
+                edge: Edge::Error
+            }
+        );
+        assert_same_result(palindrome, 1_000);
+    }
+
+    #[test]
+    fn circular_tape_is_scaled() {
+        let tape_mode = TapeMode::Circular { left: 1, right: 3 };
+        let beaver = three_state_busy_beaver().with_tape_mode(tape_mode).unwrap();
+
+        assert_same_result(beaver, 100);
+    }
+
+    #[test]
+    fn head_staying_at_the_end_is_not_reduced() {
+        let tape_mode = TapeMode::OneWay {
+            left: 1,
+            edge: Edge::Stay,
+        };
+        let beaver = three_state_busy_beaver().with_tape_mode(tape_mode).unwrap();
+        let encoding = BlockEncoding::new(&beaver);
+
+        assert!(reduce(&beaver, &encoding).is_err());
+    }
+}
@@ -0,0 +1,416 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::presets::UniverseMetadata;
+use crate::universe::machine::{Action::*, State, Write};
+use crate::universe::tape::Tape;
+use crate::universe::Symbol;
+
+use super::{Builder, Program};
+
+const ZERO: Symbol = Symbol::new(0);
+const ONE: Symbol = Symbol::new(1);
+/// Marked zero, for bits which were already copied
+const MARKED_ZERO: Symbol = Symbol::new(2);
+/// Marked one, for bits which were already copied
+const MARKED_ONE: Symbol = Symbol::new(3);
+const CELL: Symbol = Symbol::new(4);
+/// Start of the current cell
+const CURRENT: Symbol = Symbol::new(5);
+/// End of the cells, and start of the input
+const INPUT: Symbol = Symbol::new(6);
+/// End of the input, and start of the output
+const OUTPUT: Symbol = Symbol::new(7);
+/// Start of a byte of input which was read
+const CONSUMED: Symbol = Symbol::new(8);
+/// Start of the byte of input which is being read
+const READING: Symbol = Symbol::new(9);
+
+const SYMBOLS: [(Symbol, &str); 10] = [
+    (ZERO, "0"),
+    (ONE, "1"),
+    (MARKED_ZERO, "a"),
+    (MARKED_ONE, "b"),
+    (CELL, "|"),
+    (CURRENT, "^"),
+    (INPUT, "$"),
+    (OUTPUT, "#"),
+    (CONSUMED, "x"),
+    (READING, "r"),
+];
+
+const BITS: usize = 8;
+
+/// Compile a Brainfuck program, where every cell is a byte which wraps around.
+///
+/// Cells are written as 8 bits, most significant bit first, after a `|`, or `^` for the current cell.
+/// As the program only moves right of the first cell, cells are laid out from right to left, followed by the input
+/// and output, which are written the same way:
+///
+/// ```text
+/// |00000000^00000001|00000010 $ |01101000|01101001 # |01101000
+/// ```
+///
+/// `,` reads the next byte of input, after which its `|` is replaced by `x` and its bits by `a` and `b`, and leaves
+/// the cell as is once there is no more input.
+/// `.` appends the current cell to the output.
+/// Every state is named after the command it was compiled from and its position in the program, such as `inc3`.
+pub fn compile(program: &str) -> Result<Program, String> {
+    let commands: Vec<(usize, char)> = program
+        .chars()
+        .enumerate()
+        .filter(|(_, c)| "+-<>[].,".contains(*c))
+        .map(|(i, c)| (i + 1, c))
+        .collect();
+
+    // matching bracket of every bracket, by index of the command
+    let mut brackets = HashMap::new();
+    let mut open = vec![];
+    for (i, (pos, command)) in commands.iter().enumerate() {
+        match command {
+            '[' => open.push(i),
+            ']' => {
+                let start = open.pop().ok_or_else(|| format!("unmatched ] at {pos}"))?;
+                brackets.insert(start, i);
+                brackets.insert(i, start);
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = open.pop() {
+        return Err(format!("unmatched [ at {}", commands[start].0));
+    }
+
+    let name = |(pos, command): (usize, char)| {
+        let name = match command {
+            '+' => "inc",
+            '-' => "dec",
+            '>' => "right",
+            '<' => "left",
+            '[' => "loop",
+            '.' => "out",
+            _ => "in",
+        };
+        format!("{name}{pos}")
+    };
+    // a closing bracket jumps back to its opening bracket, which tests the cell again
+    let entry = |i: usize| match commands.get(i) {
+        None => State::halt().to_string(),
+        Some((_, ']')) => name(commands[brackets[&i]]),
+        Some(command) => name(*command),
+    };
+
+    let mut builder = Builder {
+        symbol_set: SYMBOLS.iter().map(|(symbol, _)| *symbol).collect(),
+        ..Default::default()
+    };
+    for (i, command) in commands.iter().enumerate() {
+        let (at, next) = (name(*command), entry(i + 1));
+        match command.1 {
+            '+' => builder.add_increment(&at, ONE, ZERO, &next),
+            '-' => builder.add_increment(&at, ZERO, ONE, &next),
+            '>' => builder.add_move_right(&at, &next),
+            '<' => builder.add_move_left(&at, &next),
+            '[' => builder.add_loop(&at, &next, &entry(brackets[&i] + 1)),
+            '.' => builder.add_output(&at, &next),
+            ',' => builder.add_input(&at, &next),
+            _ => {}
+        }
+    }
+
+    let initial_state = builder.state(&entry(0));
+    Ok(Program {
+        symbol_set: builder.symbol_set,
+        display_symbol_as: SYMBOLS
+            .iter()
+            .map(|(symbol, label)| (*symbol, label.to_string()))
+            .collect(),
+        state_set: builder.state_set,
+        display_state_as: builder.display_state_as,
+        initial_state,
+        builder: builder.builder,
+    })
+}
+
+/// Compiled program, with the given input on its tape, and the head on the first cell.
+pub fn universe_meta(program: &str, name: &str, input: &[u8]) -> Result<UniverseMetadata, String> {
+    let mut universe_meta = compile(program)?.into_universe_meta(name);
+    universe_meta.universe.tape = Tape::from_iter(input_tape(input));
+    Ok(universe_meta)
+}
+
+/// Compile the program in the given file, which is named after it.
+pub fn load(path: &Path, input: &[u8]) -> Result<UniverseMetadata, String> {
+    let program = fs::read_to_string(path)
+        .map_err(|err| format!("could not load {}: {err}", path.display()))?;
+    let name = path.file_stem().unwrap_or_default().to_string_lossy();
+
+    universe_meta(&program, &name, input)
+}
+
+/// Tape with a single empty cell, followed by the given input and no output.
+pub fn input_tape(input: &[u8]) -> Vec<Symbol> {
+    let mut tape = vec![CURRENT];
+    tape.extend(bits(0));
+    tape.push(INPUT);
+    for byte in input {
+        tape.push(CELL);
+        tape.extend(bits(*byte));
+    }
+    tape.push(OUTPUT);
+    tape
+}
+
+/// Bytes written to the output so far.
+pub fn output(tape: &Tape) -> Result<Vec<u8>, String> {
+    let symbols = tape.all_symbols();
+    let start = symbols
+        .iter()
+        .position(|s| *s == OUTPUT)
+        .ok_or_else(|| String::from("no output"))?;
+
+    symbols[start + 1..]
+        .split(|s| *s == CELL)
+        .skip(1)
+        .map(|byte| {
+            let byte: Vec<_> = byte.iter().take_while(|s| !s.is_empty()).collect();
+            if byte.len() != BITS {
+                return Err(format!("incomplete byte: {}", byte.len()));
+            }
+            byte.iter().try_fold(0_u8, |byte, bit| match **bit {
+                ZERO => Ok(byte << 1),
+                ONE => Ok(byte << 1 | 1),
+                other => Err(format!("not a bit: {other}")),
+            })
+        })
+        .collect()
+}
+
+fn bits(byte: u8) -> Vec<Symbol> {
+    (0..BITS)
+        .rev()
+        .map(|i| if byte >> i & 1 == 1 { ONE } else { ZERO })
+        .collect()
+}
+
+impl Builder {
+    /// Add one to the current cell if ones carry over, or subtract one if zeroes do.
+    fn add_increment(&mut self, at: &str, carry: Symbol, last: Symbol, next: &str) {
+        let (lsb, add, back) = (
+            format!("{at}_lsb"),
+            format!("{at}_add"),
+            format!("{at}_back"),
+        );
+        let skip = Write::None;
+
+        self.add(at, CURRENT, skip, R, &lsb);
+        self.seek(&lsb, R, &[(CELL, skip, L, &add), (INPUT, skip, L, &add)]);
+        self.add(&add, carry, Write::from(last), L, &add);
+        self.add(&add, last, Write::from(carry), L, &back);
+        self.add(&add, CURRENT, skip, N, next);
+        self.seek(&back, L, &[(CURRENT, skip, N, next)]);
+    }
+
+    /// Move to the next cell, which is to the left, adding it if there is none.
+    fn add_move_right(&mut self, at: &str, next: &str) {
+        let (find, new) = (format!("{at}_find"), |i: usize| format!("{at}_new{i}"));
+
+        self.add(at, CURRENT, Write::from(CELL), L, &find);
+        self.seek(
+            &find,
+            L,
+            &[
+                (CELL, Write::from(CURRENT), N, next),
+                (Symbol::empty(), Write::from(ZERO), L, &new(1)),
+            ],
+        );
+        for i in 1..BITS {
+            self.add(&new(i), Symbol::empty(), Write::from(ZERO), L, &new(i + 1));
+        }
+        self.add(&new(BITS), Symbol::empty(), Write::from(CURRENT), N, next);
+    }
+
+    /// Move to the previous cell, which is to the right, without a transition if there is none.
+    fn add_move_left(&mut self, at: &str, next: &str) {
+        let find = format!("{at}_find");
+
+        self.add(at, CURRENT, Write::from(CELL), R, &find);
+        self.add(&find, ZERO, Write::None, R, &find);
+        self.add(&find, ONE, Write::None, R, &find);
+        self.add(&find, CELL, Write::from(CURRENT), N, next);
+    }
+
+    /// Continue if the current cell is not zero, otherwise continue after the loop.
+    fn add_loop(&mut self, at: &str, next: &str, after: &str) {
+        let (test, zero, not_zero) = (
+            format!("{at}_test"),
+            format!("{at}_zero"),
+            format!("{at}_not_zero"),
+        );
+        let skip = Write::None;
+
+        self.add(at, CURRENT, skip, R, &test);
+        self.add(&test, ZERO, skip, R, &test);
+        self.add(&test, ONE, skip, L, &not_zero);
+        self.add(&test, CELL, skip, L, &zero);
+        self.add(&test, INPUT, skip, L, &zero);
+        self.seek(&zero, L, &[(CURRENT, skip, N, after)]);
+        self.seek(&not_zero, L, &[(CURRENT, skip, N, next)]);
+    }
+
+    /// Append the current cell to the output, one bit at a time.
+    fn add_output(&mut self, at: &str, next: &str) {
+        let (back, bit, unmark) = (
+            format!("{at}_back"),
+            format!("{at}_bit"),
+            format!("{at}_unmark"),
+        );
+        let skip = Write::None;
+
+        self.seek(at, R, &[(Symbol::empty(), Write::from(CELL), L, &back)]);
+        self.seek(&back, L, &[(CURRENT, skip, R, &bit)]);
+        self.add(&bit, MARKED_ZERO, skip, R, &bit);
+        self.add(&bit, MARKED_ONE, skip, R, &bit);
+        self.add(&bit, CELL, skip, L, &unmark);
+        self.add(&bit, INPUT, skip, L, &unmark);
+        for (value, marked) in [(ZERO, MARKED_ZERO), (ONE, MARKED_ONE)] {
+            let carry = format!("{at}_carry{value}");
+            self.add(&bit, value, Write::from(marked), R, &carry);
+            self.seek(
+                &carry,
+                R,
+                &[(Symbol::empty(), Write::from(value), L, &back)],
+            );
+        }
+        self.add(&unmark, MARKED_ZERO, Write::from(ZERO), L, &unmark);
+        self.add(&unmark, MARKED_ONE, Write::from(ONE), L, &unmark);
+        self.add(&unmark, CURRENT, skip, N, next);
+    }
+
+    /// Copy the next byte of input to the current cell, one bit at a time.
+    fn add_input(&mut self, at: &str, next: &str) {
+        let (find, bit, to_input, done) = (
+            format!("{at}_find"),
+            format!("{at}_bit"),
+            format!("{at}_to_input"),
+            format!("{at}_done"),
+        );
+        let (eof, unmark_start, unmark, finish) = (
+            format!("{at}_eof"),
+            format!("{at}_unmark_start"),
+            format!("{at}_unmark"),
+            format!("{at}_finish"),
+        );
+        let skip = Write::None;
+
+        self.seek(at, R, &[(INPUT, skip, R, &find)]);
+        self.seek(
+            &find,
+            R,
+            &[
+                (CELL, Write::from(READING), R, &bit),
+                (OUTPUT, skip, L, &eof),
+            ],
+        );
+        self.seek(&eof, L, &[(CURRENT, skip, N, next)]);
+
+        self.add(&bit, MARKED_ZERO, skip, R, &bit);
+        self.add(&bit, MARKED_ONE, skip, R, &bit);
+        self.add(&bit, CELL, skip, L, &done);
+        self.add(&bit, OUTPUT, skip, L, &done);
+        for (value, marked) in [(ZERO, MARKED_ZERO), (ONE, MARKED_ONE)] {
+            let (carry, store) = (format!("{at}_carry{value}"), format!("{at}_store{value}"));
+            self.add(&bit, value, Write::from(marked), L, &carry);
+            self.seek(&carry, L, &[(CURRENT, skip, R, &store)]);
+            self.add(&store, MARKED_ZERO, skip, R, &store);
+            self.add(&store, MARKED_ONE, skip, R, &store);
+            self.add(&store, ZERO, Write::from(marked), R, &to_input);
+            self.add(&store, ONE, Write::from(marked), R, &to_input);
+        }
+        self.seek(&to_input, R, &[(READING, skip, R, &bit)]);
+
+        self.seek(
+            &done,
+            L,
+            &[(READING, Write::from(CONSUMED), L, &unmark_start)],
+        );
+        self.seek(&unmark_start, L, &[(CURRENT, skip, R, &unmark)]);
+        self.add(&unmark, MARKED_ZERO, Write::from(ZERO), R, &unmark);
+        self.add(&unmark, MARKED_ONE, Write::from(ONE), R, &unmark);
+        self.add(&unmark, CELL, skip, L, &finish);
+        self.add(&unmark, INPUT, skip, L, &finish);
+        self.seek(&finish, L, &[(CURRENT, skip, N, next)]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::{compile, output, universe_meta};
+
+    /// Run the program on the given input, and return its output.
+    fn run(program: &str, input: &[u8]) -> Vec<u8> {
+        let mut universe = universe_meta(program, "test", input).unwrap().universe;
+
+        while !universe.machine.state.is_halted() {
+            assert!(universe.ticks < 1_000_000, "did not halt");
+            universe.tick().unwrap();
+        }
+
+        output(&universe.tape).unwrap()
+    }
+
+    #[test]
+    fn hello() {
+        let program = "++++++++[>+++++++++<-]>.    72 is H
+            +++++++++++++++++++++++++++++++++.      105 is i";
+
+        assert_eq!(run(program, b""), b"Hi");
+    }
+
+    #[test_case(b"", b"")]
+    #[test_case(b"a", b"a")]
+    #[test_case(b"cat", b"cat")]
+    fn cat(input: &[u8], expected: &[u8]) {
+        // stops at the end of input, as the cell is left as zero
+        assert_eq!(run(",[.[-],]", input), expected);
+    }
+
+    #[test]
+    fn add_input() {
+        assert_eq!(run(",>,[-<+>]<.", &[3, 4]), vec![7]);
+    }
+
+    #[test]
+    fn cells_wrap_around() {
+        assert_eq!(run("-.+.", b""), vec![255, 0]);
+    }
+
+    #[test]
+    fn nested_loops() {
+        // 3 * 4 * 5
+        assert_eq!(run("+++[>++++[>+++++<-]<-]>>.", b""), vec![60]);
+    }
+
+    #[test]
+    fn reverse() {
+        assert_eq!(run(">,[>,]<[.<]", b"abc"), b"cba");
+    }
+
+    #[test_case("[", "unmatched [ at 1")]
+    #[test_case("+]", "unmatched ] at 2")]
+    #[test_case("[[]", "unmatched [ at 1")]
+    fn unmatched_bracket(program: &str, expected: &str) {
+        assert_eq!(compile(program).unwrap_err(), String::from(expected));
+    }
+
+    #[test]
+    fn states_named_after_commands() {
+        let program = compile("+>.").unwrap();
+
+        assert_eq!(program.display_state_as[&program.initial_state], "inc1");
+        assert!(program.display_state_as.values().any(|name| name == "out3"));
+    }
+}
@@ -0,0 +1,479 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::presets::file::{parse_symbol, parse_symbol_set};
+use crate::presets::UniverseMetadata;
+use crate::universe::function::TransitionFunctionBuilder;
+use crate::universe::machine::{Action, State, Write};
+use crate::universe::Symbol;
+
+use super::Program;
+
+/// A statement and the line it is on, starting at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Line {
+    number: usize,
+    statement: Statement,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Statement {
+    Symbols(Vec<String>),
+    Label(String),
+    Write(String),
+    Move(Action),
+    If {
+        condition: Condition,
+        then: Vec<Line>,
+        otherwise: Vec<Line>,
+    },
+    While {
+        condition: Condition,
+        body: Vec<Line>,
+    },
+    Goto(String),
+    Halt,
+}
+
+/// Whether the scanned symbol is one of the given symbols, or not if negated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Condition {
+    symbols: Vec<String>,
+    negated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Instruction {
+    Write(Symbol),
+    Move(Action),
+    /// Continue if the condition holds, otherwise jump to the given instruction
+    Branch {
+        symbols: Vec<Symbol>,
+        negated: bool,
+        otherwise: usize,
+    },
+    Jump(usize),
+    Goto(String),
+    Halt,
+}
+
+/// Compile a program, where every line is one of:
+///
+/// ```text
+/// symbols <symbol>...        symbols the program works with, besides those it mentions
+/// <label>:                   label the next statement
+/// write <symbol>             write a symbol under the head
+/// left                       move the head left
+/// right                      move the head right
+/// if [not] <symbol>...       run the following lines if the scanned symbol is (not) one of the given symbols,
+/// else                       or those following else otherwise
+/// end
+/// while [not] <symbol>...    repeat the following lines while the scanned symbol is (not) one of the given symbols
+/// end
+/// goto <label>               continue at the label
+/// halt                       halt, which is also done after the last line
+/// ```
+///
+/// Symbols are given by their number or label, as in machine files, and `//` starts a comment.
+/// Every state is named after the line it was compiled from, such as `line3`.
+pub fn compile(source: &str) -> Result<Program, String> {
+    let mut lines = source
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split("//").next().unwrap_or_default().trim()))
+        .filter(|(_, line)| !line.is_empty());
+    let (statements, _) = parse_block(&mut lines, &[])?;
+
+    let mut symbols = vec![];
+    collect_symbols(&statements, &mut symbols);
+    let (symbol_set, display_symbol_as) = parse_symbol_set(&symbols.join(" "))?;
+    let symbol_set: Vec<Symbol> = symbol_set.into_iter().filter(|s| !s.is_empty()).collect();
+
+    let mut code = vec![];
+    let mut labels = HashMap::new();
+    flatten(&statements, &display_symbol_as, &mut code, &mut labels)?;
+    for (number, instruction) in code.iter_mut() {
+        if let Instruction::Goto(label) = instruction {
+            let target = labels
+                .get(label.as_str())
+                .ok_or_else(|| format!("line {number}: unknown label: {label}"))?;
+            *instruction = Instruction::Jump(*target);
+        }
+    }
+
+    let mut states = HashMap::new();
+    let mut state_set = vec![];
+    let mut display_state_as = HashMap::new();
+    for (i, (number, instruction)) in code.iter().enumerate() {
+        if let Instruction::Write(_) | Instruction::Move(_) | Instruction::Branch { .. } =
+            instruction
+        {
+            let state = State::from(states.len());
+            states.insert(i, state);
+            state_set.push(state);
+            display_state_as.insert(state, format!("line{number}"));
+        }
+    }
+
+    // follow jumps to the next instruction which has a state
+    let resolve = |mut i: usize| -> Result<State, String> {
+        for _ in 0..=code.len() {
+            match code.get(i) {
+                None | Some((_, Instruction::Halt)) => return Ok(State::halt()),
+                Some((_, Instruction::Jump(target))) => i = *target,
+                Some(_) => return Ok(states[&i]),
+            }
+        }
+        Err(format!(
+            "line {}: jumps without ever reaching a statement",
+            code[i].0
+        ))
+    };
+
+    let alphabet: Vec<Symbol> = std::iter::once(Symbol::empty())
+        .chain(symbol_set.iter().cloned())
+        .collect();
+    let mut builder = TransitionFunctionBuilder::default();
+    for (i, (_, instruction)) in code.iter().enumerate() {
+        let Some(state) = states.get(&i) else {
+            continue;
+        };
+
+        for symbol in &alphabet {
+            let (write, action, next) = match instruction {
+                Instruction::Write(print) => (Write::from(*print), Action::N, resolve(i + 1)?),
+                Instruction::Move(action) => (Write::None, *action, resolve(i + 1)?),
+                Instruction::Branch {
+                    symbols,
+                    negated,
+                    otherwise,
+                } if symbols.contains(symbol) == *negated => {
+                    (Write::None, Action::N, resolve(*otherwise)?)
+                }
+                _ => (Write::None, Action::N, resolve(i + 1)?),
+            };
+            builder.add(*state, *symbol, write, action, next);
+        }
+    }
+
+    Ok(Program {
+        symbol_set,
+        display_symbol_as,
+        state_set,
+        display_state_as,
+        initial_state: resolve(0)?,
+        builder,
+    })
+}
+
+/// Compile the program in the given file, which is named after it.
+pub fn load(path: &Path) -> Result<UniverseMetadata, String> {
+    let source = fs::read_to_string(path)
+        .map_err(|err| format!("could not load {}: {err}", path.display()))?;
+    let name = path.file_stem().unwrap_or_default().to_string_lossy();
+
+    Ok(compile(&source)?.into_universe_meta(&name))
+}
+
+/// Parse statements until one of the given keywords, which is returned if any.
+fn parse_block<'a, I: Iterator<Item = (usize, &'a str)>>(
+    lines: &mut I,
+    until: &[&'static str],
+) -> Result<(Vec<Line>, Option<&'static str>), String> {
+    let mut statements = vec![];
+    let mut last = 0;
+
+    while let Some((number, line)) = lines.next() {
+        last = number;
+        let mut words = line.split_whitespace();
+        let keyword = words.next().unwrap_or_default();
+        let args: Vec<String> = words.map(String::from).collect();
+
+        if let Some(end) = until.iter().find(|end| **end == keyword) {
+            return Ok((statements, Some(end)));
+        }
+
+        let single = |args: &[String]| match args {
+            [arg] => Ok(arg.clone()),
+            _ => Err(format!("line {number}: {keyword} expects one argument")),
+        };
+        let none = |args: &[String]| match args {
+            [] => Ok(()),
+            _ => Err(format!("line {number}: {keyword} expects no arguments")),
+        };
+        let statement = match keyword {
+            "symbols" => Statement::Symbols(args),
+            label if args.is_empty() && label.len() > 1 && label.ends_with(':') => {
+                Statement::Label(label.trim_end_matches(':').to_owned())
+            }
+            "write" => Statement::Write(single(&args)?),
+            "left" => none(&args).map(|_| Statement::Move(Action::L))?,
+            "right" => none(&args).map(|_| Statement::Move(Action::R))?,
+            "goto" => Statement::Goto(single(&args)?),
+            "halt" => none(&args).map(|_| Statement::Halt)?,
+            "if" => {
+                let condition = parse_condition(number, keyword, args)?;
+                let (then, end) = parse_block(lines, &["else", "end"])?;
+                let otherwise = match end {
+                    Some("else") => parse_block(lines, &["end"])?.0,
+                    _ => vec![],
+                };
+                Statement::If {
+                    condition,
+                    then,
+                    otherwise,
+                }
+            }
+            "while" => {
+                let condition = parse_condition(number, keyword, args)?;
+                let (body, _) = parse_block(lines, &["end"])?;
+                Statement::While { condition, body }
+            }
+            _ => return Err(format!("line {number}: unknown statement: {line}")),
+        };
+
+        statements.push(Line { number, statement });
+    }
+
+    match until.last() {
+        Some(end) => Err(format!("line {last}: expected {end}")),
+        None => Ok((statements, None)),
+    }
+}
+
+fn parse_condition(
+    number: usize,
+    keyword: &str,
+    mut args: Vec<String>,
+) -> Result<Condition, String> {
+    let negated = args.first().map(String::as_str) == Some("not");
+    if negated {
+        args.remove(0);
+    }
+
+    if args.is_empty() {
+        return Err(format!(
+            "line {number}: {keyword} expects at least one symbol"
+        ));
+    }
+    Ok(Condition {
+        symbols: args,
+        negated,
+    })
+}
+
+/// Symbols in order of appearance, with those declared by `symbols` first.
+fn collect_symbols(statements: &[Line], symbols: &mut Vec<String>) {
+    let push = |symbols: &mut Vec<String>, symbol: &String| {
+        if !symbols.contains(symbol) {
+            symbols.push(symbol.clone());
+        }
+    };
+
+    for line in statements {
+        if let Statement::Symbols(declared) = &line.statement {
+            declared.iter().for_each(|symbol| push(symbols, symbol));
+        }
+    }
+    for line in statements {
+        match &line.statement {
+            Statement::Write(symbol) => push(symbols, symbol),
+            Statement::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                condition
+                    .symbols
+                    .iter()
+                    .for_each(|symbol| push(symbols, symbol));
+                collect_symbols(then, symbols);
+                collect_symbols(otherwise, symbols);
+            }
+            Statement::While { condition, body } => {
+                condition
+                    .symbols
+                    .iter()
+                    .for_each(|symbol| push(symbols, symbol));
+                collect_symbols(body, symbols);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn flatten(
+    statements: &[Line],
+    display_symbol_as: &HashMap<Symbol, String>,
+    code: &mut Vec<(usize, Instruction)>,
+    labels: &mut HashMap<String, usize>,
+) -> Result<(), String> {
+    let parse_symbols = |symbols: &[String]| -> Result<Vec<Symbol>, String> {
+        symbols
+            .iter()
+            .map(|symbol| parse_symbol(symbol, display_symbol_as))
+            .collect()
+    };
+
+    for Line { number, statement } in statements {
+        match statement {
+            Statement::Symbols(_) => {}
+            Statement::Label(label) => {
+                if labels.insert(label.clone(), code.len()).is_some() {
+                    return Err(format!("line {number}: duplicate label: {label}"));
+                }
+            }
+            Statement::Write(symbol) => {
+                let symbol = parse_symbol(symbol, display_symbol_as)?;
+                code.push((*number, Instruction::Write(symbol)));
+            }
+            Statement::Move(action) => code.push((*number, Instruction::Move(*action))),
+            Statement::Goto(label) => code.push((*number, Instruction::Goto(label.clone()))),
+            Statement::Halt => code.push((*number, Instruction::Halt)),
+            Statement::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let branch = code.len();
+                code.push((*number, Instruction::Halt));
+                flatten(then, display_symbol_as, code, labels)?;
+
+                if !otherwise.is_empty() {
+                    let jump = code.len();
+                    code.push((*number, Instruction::Halt));
+                    let otherwise_start = code.len();
+                    flatten(otherwise, display_symbol_as, code, labels)?;
+                    code[jump].1 = Instruction::Jump(code.len());
+                    code[branch].1 = Instruction::Branch {
+                        symbols: parse_symbols(&condition.symbols)?,
+                        negated: condition.negated,
+                        otherwise: otherwise_start,
+                    };
+                } else {
+                    code[branch].1 = Instruction::Branch {
+                        symbols: parse_symbols(&condition.symbols)?,
+                        negated: condition.negated,
+                        otherwise: code.len(),
+                    };
+                }
+            }
+            Statement::While { condition, body } => {
+                let branch = code.len();
+                code.push((*number, Instruction::Halt));
+                flatten(body, display_symbol_as, code, labels)?;
+                code.push((*number, Instruction::Jump(branch)));
+                code[branch].1 = Instruction::Branch {
+                    symbols: parse_symbols(&condition.symbols)?,
+                    negated: condition.negated,
+                    otherwise: code.len(),
+                };
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::display::display_state;
+    use crate::universe::machine::State;
+
+    use super::compile;
+
+    const INCREMENT: &str = "// binary increment, starting at the first digit
+while 0 1
+    right
+end
+left
+carry:
+if 1
+    write 0
+    left
+    goto carry
+end
+write 1
+";
+
+    /// Run the program on the given input, and return its tape without surrounding empty symbols.
+    fn run(source: &str, input: &str) -> String {
+        let universe_meta = compile(source)
+            .unwrap()
+            .into_universe_meta("test")
+            .with_input(input)
+            .unwrap();
+        let labels = universe_meta.display_symbol_as.clone();
+        let mut universe = universe_meta.universe;
+
+        while !universe.machine.state.is_halted() {
+            assert!(universe.ticks < 10_000, "did not halt");
+            universe.tick().unwrap();
+        }
+
+        let tape = universe.tape.labelled(&labels).to_string();
+        tape.trim_matches('_').to_owned()
+    }
+
+    #[test_case("0", "1")]
+    #[test_case("1", "10")]
+    #[test_case("1011", "1100")]
+    #[test_case("111", "1000")]
+    fn increment(input: &str, expected: &str) {
+        assert_eq!(run(INCREMENT, input), expected);
+    }
+
+    #[test_case("aab", "bba")]
+    #[test_case("ba#b", "ab#b")]
+    fn swap_until_hash(input: &str, expected: &str) {
+        let source = "symbols a b #
+while not # _
+    if a
+        write b
+    else
+        write a
+    end
+    right
+end
+halt
+write #";
+
+        assert_eq!(run(source, input), expected);
+    }
+
+    #[test]
+    fn states_named_after_lines() {
+        let program = compile(INCREMENT).unwrap();
+        let names: Vec<String> = program
+            .state_set
+            .iter()
+            .map(|state| display_state(*state, &program.display_state_as))
+            .collect();
+
+        assert_eq!(
+            names,
+            vec!["line2", "line3", "line5", "line7", "line8", "line9", "line12"]
+        );
+        assert_eq!(program.initial_state, State::from(0));
+    }
+
+    #[test]
+    fn empty_program_halts() {
+        assert_eq!(compile("// nothing").unwrap().initial_state, State::halt());
+    }
+
+    #[test_case("jump", "line 1: unknown statement: jump")]
+    #[test_case("while 1\nright", "line 2: expected end")]
+    #[test_case("goto nowhere", "line 1: unknown label: nowhere")]
+    #[test_case("a:\na:\nright", "line 2: duplicate label: a")]
+    #[test_case("a:\ngoto a", "line 2: jumps without ever reaching a statement")]
+    #[test_case("write", "line 1: write expects one argument")]
+    #[test_case("left 2", "line 1: left expects no arguments")]
+    #[test_case("if not", "line 1: if expects at least one symbol")]
+    fn invalid_program(source: &str, expected: &str) {
+        assert_eq!(compile(source).unwrap_err(), String::from(expected));
+    }
+}
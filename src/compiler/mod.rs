@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use crate::display::DisplayStateAs;
+use crate::presets::UniverseMetadata;
+use crate::universe::function::TransitionFunctionBuilder;
+use crate::universe::machine::{Action, State, Write};
+use crate::universe::{Symbol, Universe};
+
+pub mod binary;
+pub mod brainfuck;
+pub mod language;
+
+/// Machine compiled from a program.
+#[derive(Debug, Default, Clone)]
+pub struct Program {
+    pub symbol_set: Vec<Symbol>,
+    pub display_symbol_as: HashMap<Symbol, String>,
+    pub state_set: Vec<State>,
+    /// States are named after the source line they were compiled from
+    pub display_state_as: DisplayStateAs,
+    pub initial_state: State,
+    pub builder: TransitionFunctionBuilder,
+}
+
+impl Program {
+    /// Universe with an empty tape, where the head starts at position 1.
+    pub fn into_universe_meta(self, name: &str) -> UniverseMetadata {
+        let initial_head = 1_usize;
+        let transition_function = self.builder.build();
+        let universe = Universe::new(
+            vec![],
+            initial_head,
+            self.initial_state,
+            transition_function,
+        );
+
+        UniverseMetadata {
+            name: name.to_owned(),
+            head_offset_hint: initial_head,
+            symbol_set: self.symbol_set,
+            state_set: self.state_set,
+            display_state_as: self.display_state_as,
+            display_symbol_as: self.display_symbol_as,
+            transition_function_buidler: self.builder,
+            universe,
+        }
+    }
+}
+
+/// Builds a machine, where states are created by name on first use, and `!` is the halting state.
+#[derive(Default)]
+pub(crate) struct Builder {
+    pub(crate) builder: TransitionFunctionBuilder,
+    pub(crate) state_set: Vec<State>,
+    pub(crate) display_state_as: DisplayStateAs,
+    /// Symbols other than the empty one, which `seek` moves over
+    pub(crate) symbol_set: Vec<Symbol>,
+}
+
+impl Builder {
+    pub(crate) fn state(&mut self, name: &str) -> State {
+        if name == State::halt().to_string() {
+            return State::halt();
+        }
+        if let Some((state, _)) = self.display_state_as.iter().find(|(_, n)| *n == name) {
+            return *state;
+        }
+
+        let state = State::from(self.state_set.len());
+        self.state_set.push(state);
+        self.display_state_as.insert(state, name.to_owned());
+        state
+    }
+
+    pub(crate) fn add(
+        &mut self,
+        from: &str,
+        symbol: Symbol,
+        write: Write,
+        action: Action,
+        to: &str,
+    ) {
+        let (from, to) = (self.state(from), self.state(to));
+        self.builder.add(from, symbol, write, action, to);
+    }
+
+    /// Move in the given direction until one of the given symbols, which have their own transitions.
+    ///
+    /// There is no transition for the empty symbol unless given.
+    pub(crate) fn seek(
+        &mut self,
+        from: &str,
+        action: Action,
+        stops: &[(Symbol, Write, Action, &str)],
+    ) {
+        let symbols = self.symbol_set.clone();
+        for symbol in symbols.into_iter().chain([Symbol::empty()]) {
+            match stops.iter().find(|(stop, ..)| *stop == symbol) {
+                Some((_, write, stop_action, to)) => {
+                    self.add(from, symbol, *write, *stop_action, to)
+                }
+                None if !symbol.is_empty() => self.add(from, symbol, Write::None, action, from),
+                None => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use nannou::prelude::{App, Key, MouseButton};
+
+use crate::presets::UniverseMetadata;
+use crate::universe::function::Output;
+use crate::universe::machine::{Action, State, Write};
+use crate::universe::Symbol;
+
+use super::{
+    AnimateMoving, Model, CELL_HEIGHT, CELL_WIDTH, CELL_Y_OFFSET, TRANSITION_FUNCTION_LINE_HEIGHT,
+    TRANSITION_FUNCTION_Y_OFFSET,
+};
+
+pub const EDITOR_HELP: &str =
+    "click cell: cycle symbol | click line: select | W/M/N: cycle write/move/next state | A: add state | S: save | space: pause";
+
+const STATE_LETTERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+pub fn mouse_pressed(app: &App, model: &mut Model, button: MouseButton) {
+    if button != MouseButton::Left {
+        return;
+    }
+
+    let (x, y) = (app.mouse.x, app.mouse.y);
+    if (y - CELL_Y_OFFSET).abs() <= CELL_HEIGHT / 2.0 {
+        let offset = match model.options.animate_moving {
+            AnimateMoving::Tape => model.head_position(model.progress(app.time)),
+            AnimateMoving::Machine => 0.0,
+        };
+        model.cycle_symbol((x / CELL_WIDTH + offset).round() as isize);
+    } else if let Some(index) = model.line_at(y) {
+        model.selected = Some(index);
+    }
+}
+
+pub fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    match key {
+        Key::Space => model.paused = !model.paused,
+        Key::W => model.edit_selected(|model, output| Output {
+            write: next(&model.writes(), &output.write),
+            ..output
+        }),
+        Key::M => model.edit_selected(|_, output| Output {
+            action: next(&[Action::L, Action::R, Action::N], &output.action),
+            ..output
+        }),
+        Key::N => model.edit_selected(|model, output| Output {
+            state: next(&model.states(), &output.state),
+            ..output
+        }),
+        Key::A => model.add_state(),
+        Key::S => {
+            let path = model.options.save_path.clone();
+            model.message = Some(match model.metadata().save(&path) {
+                Ok(()) => format!("saved to {}", path.display()),
+                Err(err) => err,
+            });
+        }
+        _ => {}
+    }
+}
+
+/// Item after the given one, wrapping around.
+fn next<T: PartialEq + Copy>(items: &[T], current: &T) -> T {
+    let index = items.iter().position(|item| item == current);
+    items[index.map(|i| (i + 1) % items.len()).unwrap_or(0)]
+}
+
+impl Model {
+    /// Index of the transition drawn at the given height.
+    fn line_at(&self, y: f32) -> Option<usize> {
+        let line = (y - TRANSITION_FUNCTION_Y_OFFSET) / TRANSITION_FUNCTION_LINE_HEIGHT;
+        let len = self.builder.added().len();
+        if line < -0.5 || line.round() as usize >= len {
+            return None;
+        }
+
+        // lines are drawn in reverse
+        Some(len - 1 - line.round() as usize)
+    }
+
+    /// Symbols which may be written, including the empty symbol.
+    fn symbols(&self) -> Vec<Symbol> {
+        let mut symbols = self.symbol_set.clone();
+        if !symbols.contains(&Symbol::empty()) {
+            symbols.insert(0, Symbol::empty());
+        }
+        symbols
+    }
+
+    fn writes(&self) -> Vec<Write> {
+        let mut writes: Vec<_> = self.symbols().into_iter().map(Write::Print).collect();
+        writes.extend([Write::Erase, Write::Delete, Write::None]);
+        writes
+    }
+
+    /// Declared states and states used in the transition function, followed by halt.
+    fn states(&self) -> Vec<State> {
+        let mut states = self.state_set.clone();
+        for (input, output) in self.builder.added() {
+            for state in [input.state, output.state] {
+                if !state.is_halted() && !states.contains(&state) {
+                    states.push(state);
+                }
+            }
+        }
+
+        states.push(State::halt());
+        states
+    }
+
+    fn cycle_symbol(&mut self, pos: isize) {
+        self.restart_if_stopped();
+        let symbol = next(&self.symbols(), &self.universe.tape.read(pos));
+        self.universe.tape.write(Write::Print(symbol), pos);
+        // the tape is part of the initial configuration until the machine moves
+        if self.universe.ticks == 0 {
+            self.initial.tape = self.universe.tape.clone();
+        }
+        self.edited();
+    }
+
+    fn edit_selected(&mut self, edit: impl Fn(&Model, Output) -> Output) {
+        let Some(index) = self.selected else {
+            return;
+        };
+        let Some((_, output)) = self.builder.added().get(index).cloned() else {
+            return;
+        };
+
+        let output = edit(self, output);
+        if self.builder.set_output(index, output).is_ok() {
+            self.edited();
+        }
+    }
+
+    /// Add a state which, for every symbol, leaves the tape as is and halts.
+    fn add_state(&mut self) {
+        let states = self.states();
+        let state = (0..)
+            .map(State::from)
+            .find(|state| !states.contains(state))
+            .unwrap_or_default();
+        let name = STATE_LETTERS
+            .chars()
+            .map(String::from)
+            .find(|name| !self.state_as.values().any(|n| n == name))
+            .unwrap_or_else(|| state.to_string());
+
+        self.state_set.push(state);
+        self.state_as.insert(state, name);
+        for symbol in self.symbols() {
+            self.builder
+                .add(state, symbol, Write::None, Action::N, State::halt());
+        }
+
+        self.selected = Some(self.builder.added().len() - 1);
+        self.edited();
+    }
+
+    /// Let the universe continue with the edited machine, or start over if it already stopped.
+    fn edited(&mut self) {
+        self.restart_if_stopped();
+        self.universe
+            .machine
+            .set_transition_function(self.builder.build());
+        self.message = None;
+    }
+
+    /// Go back to the initial configuration once the universe halted or failed.
+    fn restart_if_stopped(&mut self) {
+        if self.summary.is_some() || self.universe.machine.state.is_halted() {
+            self.universe = self.initial.clone();
+            self.animation_queue.clear();
+            self.summary = None;
+        }
+    }
+
+    /// The initial configuration with the edited machine.
+    pub(super) fn metadata(&self) -> UniverseMetadata {
+        let mut universe = self.initial.clone();
+        universe
+            .machine
+            .set_transition_function(self.builder.build());
+
+        UniverseMetadata {
+            name: self.name.clone(),
+            head_offset_hint: universe.pos.max(0) as usize,
+            symbol_set: self.symbol_set.clone(),
+            state_set: self.state_set.clone(),
+            display_state_as: self.state_as.clone(),
+            display_symbol_as: self.symbol_as.clone(),
+            transition_function_buidler: self.builder.clone(),
+            universe,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::display::animation::Model;
+    use crate::presets::busy_beaver::two_state_busy_beaver;
+    use crate::universe::function::Output;
+    use crate::universe::machine::{Action, State, Write};
+    use crate::universe::Symbol;
+
+    use super::next;
+
+    #[test]
+    fn next_wraps_around() {
+        assert_eq!(next(&[1, 2, 3], &2), 3);
+        assert_eq!(next(&[1, 2, 3], &3), 1);
+        assert_eq!(next(&[1, 2, 3], &4), 1);
+    }
+
+    #[test]
+    fn cycle_symbol_on_tape() {
+        let mut model = Model::from(two_state_busy_beaver());
+
+        model.cycle_symbol(3);
+        assert_eq!(model.universe.tape.read(3), Symbol::from(1));
+        model.cycle_symbol(3);
+        assert_eq!(model.universe.tape.read(3), Symbol::empty());
+    }
+
+    #[test]
+    fn edit_selected_line_changes_machine() {
+        let mut model = Model::from(two_state_busy_beaver());
+        model.selected = Some(0);
+
+        model.edit_selected(|_, output| Output {
+            state: State::halt(),
+            ..output
+        });
+
+        let (_, output) = model.builder.added()[0];
+        assert_eq!(output.state, State::halt());
+
+        model.universe.tick().unwrap();
+        assert!(model.universe.machine.state.is_halted());
+    }
+
+    #[test]
+    fn add_state_adds_transition_per_symbol() {
+        let mut model = Model::from(two_state_busy_beaver());
+        let before = model.builder.added().len();
+
+        model.add_state();
+
+        let state = State::from(2);
+        assert_eq!(model.builder.added().len(), before + 2);
+        assert_eq!(model.state_as[&state], String::from("C"));
+        assert_eq!(model.selected, Some(before + 1));
+        assert!(model
+            .builder
+            .added()
+            .iter()
+            .all(|(input, output)| input.state != state
+                || (output.write == Write::None && output.action == Action::N)));
+    }
+
+    fn halted_beaver() -> Model {
+        let mut model = Model::from(two_state_busy_beaver());
+        while !model.universe.machine.state.is_halted() {
+            model.universe.tick().unwrap();
+        }
+        model
+    }
+
+    #[test]
+    fn edit_after_halting_restarts() {
+        let mut model = halted_beaver();
+        model.selected = Some(0);
+
+        model.edit_selected(|_, output| Output {
+            action: Action::L,
+            ..output
+        });
+
+        assert_eq!(model.universe.ticks, 0);
+        assert_eq!(model.universe.machine.state, State::from(0));
+        assert!(model.universe.tape.is_empty());
+
+        // the restarted universe uses the edited machine
+        let pos = model.universe.pos;
+        model.universe.tick().unwrap();
+        assert_eq!(model.universe.pos, pos - 1);
+    }
+
+    #[test]
+    fn save_writes_initial_configuration() {
+        let mut model = halted_beaver();
+        model.selected = Some(0);
+        model.edit_selected(|_, output| Output {
+            action: Action::L,
+            ..output
+        });
+        model.universe.tick().unwrap();
+
+        let metadata = model.metadata();
+        assert_eq!(metadata.universe.ticks, 0);
+        assert_eq!(metadata.universe.machine.state, State::from(0));
+        assert!(metadata.universe.tape.is_empty());
+        assert_eq!(
+            metadata.transition_function_buidler.added()[0].1.action,
+            Action::L
+        );
+    }
+
+    #[test]
+    fn line_at_is_reversed() {
+        let model = Model::from(two_state_busy_beaver());
+
+        assert_eq!(
+            model.line_at(super::TRANSITION_FUNCTION_Y_OFFSET),
+            Some(model.builder.added().len() - 1)
+        );
+        assert_eq!(model.line_at(-1000.0), None);
+    }
+}
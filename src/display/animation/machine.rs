@@ -1,21 +1,29 @@
 use nannou::Draw;
 
-use crate::display::{display_state, DisplayStateAs};
+use crate::display::{
+    display_state, display_symbol, display_write, DisplayStateAs, DisplaySymbolAs,
+};
 use crate::universe::function::{Input, Output, TransitionFunctionBuilder};
 use crate::universe::machine::Machine;
 
 use super::{
-    CELL_HEIGHT, CELL_OUTLINE_COLOR, CELL_STROKE_WIDTH, CELL_WIDTH, CELL_Y_OFFSET, STEPS_Y_OFFSET,
+    Theme, CELL_HEIGHT, CELL_STROKE_WIDTH, CELL_WIDTH, CELL_Y_OFFSET, STEPS_Y_OFFSET,
     TRANSITION_FUNCTION_LINE_HEIGHT, TRANSITION_FUNCTION_Y_OFFSET, TURING_MACHINE_HEIGHT,
     TURING_MACHINE_WIDTH, TURING_MACHINE_Y_OFFSET,
 };
 
-pub fn draw_machine(machine: &Machine, pos: isize, state_as: &DisplayStateAs, draw: &Draw) {
-    let position = CELL_WIDTH * (pos - 1) as f32;
+pub fn draw_machine(
+    machine: &Machine,
+    pos: f32,
+    state_as: &DisplayStateAs,
+    theme: Theme,
+    draw: &Draw,
+) {
+    let position = CELL_WIDTH * (pos - 1.0);
 
     // whole machine
     draw.rect()
-        .stroke_color(CELL_OUTLINE_COLOR)
+        .stroke_color(theme.outline())
         .stroke_weight(CELL_STROKE_WIDTH)
         .no_fill()
         .w(TURING_MACHINE_WIDTH)
@@ -24,7 +32,7 @@ pub fn draw_machine(machine: &Machine, pos: isize, state_as: &DisplayStateAs, dr
 
     // pointer
     draw.rect()
-        .stroke_color(CELL_OUTLINE_COLOR)
+        .stroke_color(theme.outline())
         .stroke_weight(CELL_STROKE_WIDTH)
         .no_fill()
         .w(CELL_WIDTH + CELL_WIDTH / 4.0)
@@ -34,15 +42,16 @@ pub fn draw_machine(machine: &Machine, pos: isize, state_as: &DisplayStateAs, dr
     // state
     let state = display_state(machine.state, state_as);
     draw.text(state.as_str())
+        .color(theme.text())
         .x_y(position, TURING_MACHINE_Y_OFFSET)
         .center_justify();
 }
 
-pub fn draw_steps(steps: usize, pos: isize, draw: &Draw) {
+pub fn draw_steps(steps: usize, pos: isize, theme: Theme, draw: &Draw) {
     let position = CELL_WIDTH * (pos - 1) as f32;
 
     draw.rect()
-        .stroke_color(CELL_OUTLINE_COLOR)
+        .stroke_color(theme.outline())
         .stroke_weight(CELL_STROKE_WIDTH)
         .no_fill()
         .w(TURING_MACHINE_WIDTH)
@@ -50,6 +59,7 @@ pub fn draw_steps(steps: usize, pos: isize, draw: &Draw) {
         .x_y(position, STEPS_Y_OFFSET);
 
     draw.text(steps.to_string().as_str())
+        .color(theme.text())
         .center_justify()
         .align_text_middle_y()
         .w(TURING_MACHINE_WIDTH)
@@ -60,10 +70,16 @@ pub fn draw_steps(steps: usize, pos: isize, draw: &Draw) {
 pub fn draw_transition_function(
     builder: &TransitionFunctionBuilder,
     state_as: &DisplayStateAs,
+    symbol_as: &DisplaySymbolAs,
+    selected: Option<usize>,
+    theme: Theme,
     draw: &Draw,
 ) {
-    for (pos, (input, output)) in builder.added().iter().rev().enumerate() {
-        draw_function_line(*input, *output, pos, state_as, draw)
+    let added = builder.added();
+    for (pos, (input, output)) in added.iter().rev().enumerate() {
+        let is_selected = selected == Some(added.len() - 1 - pos);
+        let display_as = (state_as, symbol_as);
+        draw_function_line(*input, *output, pos, is_selected, display_as, theme, draw)
     }
 }
 
@@ -71,17 +87,29 @@ pub fn draw_function_line(
     input: Input,
     output: Output,
     pos: usize,
-    state_as: &DisplayStateAs,
+    is_selected: bool,
+    (state_as, symbol_as): (&DisplayStateAs, &DisplaySymbolAs),
+    theme: Theme,
     draw: &Draw,
 ) {
-    let (state, symbol) = (display_state(input.state, state_as), input.symbol);
+    let (state, symbol) = (
+        display_state(input.state, state_as),
+        display_symbol(input.symbol, symbol_as),
+    );
     let (write, action, o_state) = (
-        output.write,
+        display_write(output.write, symbol_as),
         output.action,
         display_state(output.state, state_as),
     );
 
+    let color = if is_selected {
+        theme.outline()
+    } else {
+        theme.text()
+    };
+
     draw.text(format!("{state}, {symbol} -> {write}, {action}, {o_state}").as_str())
+        .color(color)
         .x_y(
             0_f32,
             TRANSITION_FUNCTION_Y_OFFSET + (TRANSITION_FUNCTION_LINE_HEIGHT * pos as f32),
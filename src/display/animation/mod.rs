@@ -4,24 +4,36 @@ use std::{collections::VecDeque, time::Duration};
 
 use crate::presets::UniverseMetadata;
 use crate::universe::function::TransitionFunctionBuilder;
-use crate::universe::machine::{Action, Write};
-use crate::universe::Universe;
+use crate::universe::machine::{self as universe_machine, Action, Write};
+use crate::universe::{Symbol, Universe};
 
 use clap::ValueEnum;
 use nannou::prelude::*;
-use once_cell::sync::OnceCell;
 
+pub use self::options::{AnimationOptions, Theme};
+
+use self::editor::{key_pressed, mouse_pressed, EDITOR_HELP};
 use self::machine::{draw_machine, draw_steps, draw_transition_function};
-use self::tape::draw_tape;
+use self::summary::{draw_summary, Summary};
+use self::tape::{draw_tape, FadeIn};
 
-use super::{display_state, DisplayStateAs};
+use super::{display_state, display_symbol, DisplayStateAs, DisplaySymbolAs};
 
+mod editor;
 mod machine;
+mod options;
+mod summary;
 mod tape;
 
 const WINDOW_TITLE: &str = "My Little Turing Machine";
 
 const DEFAULT_TICK_SPEED: Duration = Duration::from_secs(1);
+
+// share of a single tick spent on each animation state
+const WRITING_SHARE: f32 = 0.4;
+const MOVING_SHARE: f32 = 0.5;
+const READING_SHARE: f32 = 0.1;
+
 const DISPLAY_TAPE_HALF_WIDTH: usize = 50;
 
 const TRANSITION_FUNCTION_LINE_HEIGHT: f32 = 15_f32;
@@ -37,12 +49,17 @@ const TURING_MACHINE_Y_OFFSET: f32 = CELL_HEIGHT + CELL_HEIGHT;
 const CELL_Y_OFFSET: f32 = 0.0;
 const TRANSITION_FUNCTION_Y_OFFSET: f32 = 1.5 * TURING_MACHINE_HEIGHT + CELL_HEIGHT;
 
-const CELL_OUTLINE_COLOR: nannou::prelude::rgb::Srgb<u8> = STEELBLUE;
-
 const STEPS_Y_OFFSET: f32 = -1.5 * CELL_HEIGHT;
 
-// workaround for nannou API so we can pass model
-static MODEL: OnceCell<Mutex<Model>> = OnceCell::new();
+const EDITOR_HELP_Y_OFFSET: f32 = -5.0 * TURING_MACHINE_HEIGHT;
+
+const SUMMARY_WIDTH: f32 = 300_f32;
+const SUMMARY_HEIGHT: f32 = 100_f32;
+const SUMMARY_Y_OFFSET: f32 = STEPS_Y_OFFSET - TURING_MACHINE_HEIGHT - SUMMARY_HEIGHT / 2.0;
+
+// global hand-off forced by nannou 0.18: `nannou::app` only takes a plain `fn(&App) -> Model` pointer, which can not
+// capture the universe to animate, so `animate` leaves its model here and the model function takes it right away
+static PENDING_MODEL: Mutex<Option<Model>> = Mutex::new(None);
 
 #[derive(Debug, Clone, Copy)]
 enum Direction {
@@ -51,37 +68,67 @@ enum Direction {
 }
 
 /// State used for drawing purposes
+///
+/// Each state takes up a share of the tick speed, during which it is tweened.
 #[derive(Debug, Clone)]
 enum State {
     Moving(Direction),
     Reading,
     Writing(String),
     Erasing,
-    Halted,
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Default, Clone)]
 struct Model {
+    name: String,
+    symbol_set: Vec<Symbol>,
+    state_set: Vec<universe_machine::State>,
     builder: TransitionFunctionBuilder,
+    /// Index of the transition selected for editing
+    selected: Option<usize>,
+    paused: bool,
+    /// Feedback shown below the editor help
+    message: Option<String>,
     animation_queue: VecDeque<State>,
+    /// App time at which the state in front of the queue started
+    state_started: f32,
     state_as: DisplayStateAs,
+    symbol_as: DisplaySymbolAs,
     universe: Universe,
-    full_screen: bool,
-    animate_moving: AnimateMoving,
-    show_tick_count: bool,
+    /// Configuration the universe started from, which is saved and restarted from after edits
+    initial: Universe,
+    /// Set once the universe stopped, after which it is no longer ticked
+    summary: Option<Summary>,
+    options: AnimationOptions,
 }
 
 /// When animating, decide whether to move the machine or tape
-#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq, Default)]
 pub enum AnimateMoving {
     Machine,
+    #[default]
     Tape,
 }
 
-impl Default for AnimateMoving {
-    fn default() -> Self {
-        AnimateMoving::Tape
+impl Direction {
+    /// Displacement of the head in cells
+    fn offset(&self) -> f32 {
+        match self {
+            Direction::Left => -1.0,
+            Direction::Right => 1.0,
+        }
+    }
+}
+
+impl State {
+    fn duration(&self, tick_speed: Duration) -> f32 {
+        let share = match self {
+            State::Moving(_) => MOVING_SHARE,
+            State::Reading => READING_SHARE,
+            State::Writing(_) | State::Erasing => WRITING_SHARE,
+        };
+
+        tick_speed.as_secs_f32() * share
     }
 }
 
@@ -93,78 +140,96 @@ impl From<UniverseMetadata> for Model {
         };
 
         Model {
+            name: value.name,
+            symbol_set: value.symbol_set,
+            state_set: value.state_set,
             builder: value.transition_function_buidler,
+            initial: universe.clone(),
             universe,
             state_as: value.display_state_as,
+            symbol_as: value.display_symbol_as,
             ..Default::default()
         }
     }
 }
 
-// TODO have some `Options` struct
-pub fn animate(
-    metadata: UniverseMetadata,
-    tick_speed: Option<Duration>,
-    full_screen: bool,
-    move_item: AnimateMoving,
-    show_tick_count: bool,
-) {
-    set_model(metadata, full_screen, move_item, show_tick_count);
+/// Animate the universe in a new window.
+///
+/// Does not return, as the nannou event loop exits the process once the window is closed.
+pub fn animate(metadata: UniverseMetadata, options: AnimationOptions) {
+    let mut pending = Model::from(metadata);
+    pending.options = options;
+    *PENDING_MODEL.lock().unwrap() = Some(pending);
 
     nannou::app(model)
         .update(update)
-        .loop_mode(LoopMode::Rate {
-            update_interval: tick_speed.unwrap_or(DEFAULT_TICK_SPEED),
-        })
+        .loop_mode(LoopMode::RefreshSync)
         .run();
 }
 
-fn set_model(
-    metadata: UniverseMetadata,
-    full_screen: bool,
-    move_item: AnimateMoving,
-    show_tick_count: bool,
-) {
-    let model_static = MODEL.get_or_init(|| Mutex::new(Model::default()));
-    let mut model = Model::from(metadata);
-    model.full_screen = full_screen;
-    model.animate_moving = move_item;
-    model.show_tick_count = show_tick_count;
-
-    *model_static.lock().unwrap() = model;
-}
-
-/// Panics if model was never set.
-///
-/// Workaround because we cannot pass to api as argument, and we cannot capture variables in closures.
+/// Takes the model left by `animate`, and panics if there is none.
 fn model(app: &App) -> Model {
-    let model = MODEL.get_or_init(|| Mutex::new(Model::default()));
-    let model = model.lock().unwrap().clone();
-
-    let view_builder = app.new_window().title(WINDOW_TITLE).view(view);
-    let view_builder = if model.full_screen {
-        view_builder.fullscreen()
-    } else {
-        view_builder
+    let model = PENDING_MODEL
+        .lock()
+        .unwrap()
+        .take()
+        .expect("model should be set before building the app");
+
+    let view_builder = app
+        .new_window()
+        .title(WINDOW_TITLE)
+        .view(view)
+        .mouse_pressed(mouse_pressed)
+        .key_pressed(key_pressed);
+    let view_builder = match model.options.window_size {
+        _ if model.options.full_screen => view_builder.fullscreen(),
+        Some((width, height)) => view_builder.size(width, height),
+        None => view_builder,
     };
     view_builder.build().unwrap();
 
     model
 }
 
-fn update(_app: &App, model: &mut Model, _update: Update) {
-    let front = model.animation_queue.pop_front();
-    if !matches!(front, Some(State::Reading) | None) {
+fn update(app: &App, model: &mut Model, _update: Update) {
+    if model.paused {
+        return;
+    }
+
+    if let Some(state) = model.animation_queue.front() {
+        if app.time - model.state_started < state.duration(model.options.tick_speed) {
+            return;
+        }
+
+        model.animation_queue.pop_front();
+        model.state_started = app.time;
+        if !model.animation_queue.is_empty() {
+            return;
+        }
+    }
+
+    if model.summary.is_some() {
+        return;
+    } else if model.universe.machine.state.is_halted() {
+        model.summary = Some(Summary::new(&model.universe, &model.state_as, None));
         return;
     }
 
+    let (print, action) = match model.universe.tick() {
+        Ok(output) => output,
+        Err(err) => {
+            model.summary = Some(Summary::new(&model.universe, &model.state_as, Some(err)));
+            return;
+        }
+    };
+
     let queue = &mut model.animation_queue;
-    let (print, action) = model.universe.tick().unwrap();
-    queue.push_back(match print {
-        Write::Print(x) => State::Writing(format!("{x}")),
-        Write::Erase => State::Erasing,
-        Write::None => State::Halted,
-    });
+    match print {
+        Write::Print(x) if x.is_empty() => queue.push_back(State::Writing(String::new())),
+        Write::Print(x) => queue.push_back(State::Writing(display_symbol(x, &model.symbol_as))),
+        Write::Erase | Write::Delete => queue.push_back(State::Erasing),
+        Write::None => {}
+    }
 
     match action {
         Action::L => queue.push_back(State::Moving(Direction::Left)),
@@ -173,32 +238,105 @@ fn update(_app: &App, model: &mut Model, _update: Update) {
     }
 
     queue.push_back(State::Reading);
+    model.state_started = app.time;
+}
+
+impl Model {
+    /// Progress of the state in front of the queue, between 0 and 1.
+    fn progress(&self, time: f32) -> f32 {
+        let Some(state) = self.animation_queue.front() else {
+            return 1.0;
+        };
+
+        let duration = state.duration(self.options.tick_speed);
+        if duration <= 0.0 {
+            1.0
+        } else {
+            ((time - self.state_started) / duration).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Position of the head as shown, which lags behind the universe until a pending move is done.
+    fn head_position(&self, progress: f32) -> f32 {
+        let pos = self.universe.pos as f32;
+        let pending_move =
+            self.animation_queue
+                .iter()
+                .enumerate()
+                .find_map(|(i, state)| match state {
+                    State::Moving(direction) => Some((i, direction)),
+                    _ => None,
+                });
+
+        match pending_move {
+            Some((0, direction)) => pos - direction.offset() * (1.0 - progress),
+            Some((_, direction)) => pos - direction.offset(),
+            None => pos,
+        }
+    }
+
+    /// Symbol which is being written, fading in as the state progresses.
+    fn fade_in(&self, progress: f32) -> Option<FadeIn<'_>> {
+        match self.animation_queue.front() {
+            Some(State::Writing(symbol)) => Some(FadeIn {
+                pos: self.head_position(progress).round() as isize,
+                text: symbol,
+                alpha: progress,
+            }),
+            _ => None,
+        }
+    }
 }
 
 fn view(app: &App, model: &Model, frame: Frame) {
-    let Some(_state) = model.animation_queue.front() else {
+    if model.animation_queue.is_empty() && model.summary.is_none() {
         return;
-    };
+    }
 
     let draw = app.draw();
     let universe = &model.universe;
 
     // reset background
-    draw.background().color(BLACK);
+    let theme = model.options.theme;
+    draw.background().color(theme.background());
+
+    draw_transition_function(
+        &model.builder,
+        &model.state_as,
+        &model.symbol_as,
+        model.selected,
+        theme,
+        &draw,
+    );
+
+    let progress = model.progress(app.time);
+    let head = model.head_position(progress);
+    let (offset, pos) = match model.options.animate_moving {
+        AnimateMoving::Tape => (head, 0.0),
+        AnimateMoving::Machine => (0.0, head),
+    };
 
-    draw_transition_function(&model.builder, &model.state_as, &draw);
+    let fade_in = model.fade_in(progress);
+    draw_tape(universe, &model.symbol_as, offset, fade_in, theme, &draw);
+    draw_machine(&universe.machine, pos, &model.state_as, theme, &draw);
 
-    let (offset, pos) = match model.animate_moving {
-        AnimateMoving::Tape => (universe.pos, 0),
-        AnimateMoving::Machine => (0, universe.pos),
-    };
+    if model.options.show_tick_count {
+        draw_steps(universe.ticks, 0, theme, &draw);
+    }
 
-    draw_tape(&universe.tape, universe.pos, offset, &draw);
-    draw_machine(&universe.machine, pos, &model.state_as, &draw);
+    if let Some(summary) = &model.summary {
+        draw_summary(summary, theme, &draw);
+    }
 
-    if model.show_tick_count {
-        draw_steps(universe.ticks, 0, &draw);
+    let mut help = String::from(EDITOR_HELP);
+    if let Some(message) = &model.message {
+        help.push_str(&format!("\n{message}"));
     }
+    draw.text(&help)
+        .color(theme.text())
+        .w(app.window_rect().w())
+        .x_y(0_f32, EDITOR_HELP_Y_OFFSET)
+        .center_justify();
 
     draw.to_frame(app, &frame).unwrap();
 }
@@ -210,7 +348,7 @@ fn draw_debug_info(draw: &Draw, model: &Model) {
         format!(
             "head position: {}\ntape: {}\nmachine state: {}\ndraw state: {:?}",
             model.universe.pos,
-            model.universe.tape,
+            model.universe.tape.labelled(&model.symbol_as),
             display_state(model.universe.machine.state, &model.state_as),
             model.animation_queue.back()
         )
@@ -221,9 +359,11 @@ fn draw_debug_info(draw: &Draw, model: &Model) {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use crate::presets::busy_beaver::three_state_busy_beaver;
 
-    use super::Model;
+    use super::{AnimationOptions, Direction, Model, State};
 
     #[test]
     fn model_from_beaver_3_universe_meta() {
@@ -239,4 +379,39 @@ mod tests {
         assert_eq!(model.universe.tape, beaver.universe.tape);
         assert_eq!(model.universe.machine, beaver.universe.machine);
     }
+
+    #[test]
+    fn head_lags_behind_until_moved() {
+        let mut model = Model::from(three_state_busy_beaver());
+        model.universe.pos = 3;
+        model.animation_queue.extend([
+            State::Writing(String::from("1")),
+            State::Moving(Direction::Right),
+            State::Reading,
+        ]);
+
+        assert_eq!(model.head_position(0.5), 2.0);
+        assert_eq!(model.fade_in(0.5).map(|fade_in| fade_in.pos), Some(2));
+
+        model.animation_queue.pop_front();
+        assert_eq!(model.head_position(0.25), 2.25);
+        assert!(model.fade_in(0.25).is_none());
+
+        model.animation_queue.pop_front();
+        assert_eq!(model.head_position(0.0), 3.0);
+    }
+
+    #[test]
+    fn progress_is_clamped() {
+        let mut model = Model::from(three_state_busy_beaver());
+        model.options = AnimationOptions::default().tick_speed(Duration::from_secs(1));
+        model.state_started = 1.0;
+        model
+            .animation_queue
+            .push_back(State::Moving(Direction::Left));
+
+        assert_eq!(model.progress(1.0), 0.0);
+        assert_eq!(model.progress(1.25), 0.5);
+        assert_eq!(model.progress(3.0), 1.0);
+    }
 }
@@ -0,0 +1,151 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::ValueEnum;
+use nannou::color::{Srgb, BLACK, DARKSLATEGRAY, STEELBLUE, WHITE, WHITESMOKE};
+
+use super::{AnimateMoving, DEFAULT_TICK_SPEED};
+
+const DEFAULT_SAVE_PATH: &str = "machine.tm";
+
+/// Options for animating a universe, built using chained setters.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use little_turing_machine::display::animation::{AnimateMoving, AnimationOptions, Theme};
+/// let _options = AnimationOptions::default()
+///     .tick_speed(Duration::from_millis(500))
+///     .animate_moving(AnimateMoving::Machine)
+///     .theme(Theme::Light)
+///     .window_size(800, 600);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnimationOptions {
+    pub(super) tick_speed: Duration,
+    pub(super) full_screen: bool,
+    pub(super) animate_moving: AnimateMoving,
+    pub(super) show_tick_count: bool,
+    pub(super) theme: Theme,
+    pub(super) window_size: Option<(u32, u32)>,
+    pub(super) save_path: PathBuf,
+}
+
+/// Colors used when drawing
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> Self {
+        AnimationOptions {
+            tick_speed: DEFAULT_TICK_SPEED,
+            full_screen: false,
+            animate_moving: AnimateMoving::default(),
+            show_tick_count: false,
+            theme: Theme::default(),
+            window_size: None,
+            save_path: PathBuf::from(DEFAULT_SAVE_PATH),
+        }
+    }
+}
+
+impl AnimationOptions {
+    /// Time it takes to animate a single tick of the universe.
+    pub fn tick_speed(mut self, tick_speed: Duration) -> Self {
+        self.tick_speed = tick_speed;
+        self
+    }
+
+    pub fn full_screen(mut self, full_screen: bool) -> Self {
+        self.full_screen = full_screen;
+        self
+    }
+
+    pub fn animate_moving(mut self, animate_moving: AnimateMoving) -> Self {
+        self.animate_moving = animate_moving;
+        self
+    }
+
+    pub fn show_tick_count(mut self, show_tick_count: bool) -> Self {
+        self.show_tick_count = show_tick_count;
+        self
+    }
+
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Window width and height in points, ignored when full screen.
+    pub fn window_size(mut self, width: u32, height: u32) -> Self {
+        self.window_size = Some((width, height));
+        self
+    }
+
+    /// File to which the machine is saved from the editor.
+    pub fn save_path(mut self, save_path: PathBuf) -> Self {
+        self.save_path = save_path;
+        self
+    }
+}
+
+impl Theme {
+    pub fn background(&self) -> Srgb<u8> {
+        match self {
+            Theme::Dark => BLACK,
+            Theme::Light => WHITESMOKE,
+        }
+    }
+
+    pub fn outline(&self) -> Srgb<u8> {
+        match self {
+            Theme::Dark => STEELBLUE,
+            Theme::Light => DARKSLATEGRAY,
+        }
+    }
+
+    pub fn text(&self) -> Srgb<u8> {
+        match self {
+            Theme::Dark => WHITE,
+            Theme::Light => BLACK,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use crate::display::animation::AnimateMoving;
+
+    use super::{AnimationOptions, Theme};
+
+    #[test]
+    fn default_options() {
+        let options = AnimationOptions::default();
+
+        assert_eq!(options.tick_speed, Duration::from_secs(1));
+        assert!(!options.full_screen);
+        assert_eq!(options.animate_moving, AnimateMoving::Tape);
+        assert!(!options.show_tick_count);
+        assert_eq!(options.theme, Theme::Dark);
+        assert_eq!(options.window_size, None);
+        assert_eq!(options.save_path, PathBuf::from("machine.tm"));
+    }
+
+    #[test]
+    fn chained_setters() {
+        let options = AnimationOptions::default()
+            .full_screen(true)
+            .show_tick_count(true)
+            .window_size(640, 480);
+
+        assert!(options.full_screen);
+        assert!(options.show_tick_count);
+        assert_eq!(options.window_size, Some((640, 480)));
+    }
+}
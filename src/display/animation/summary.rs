@@ -0,0 +1,111 @@
+use nannou::color::srgba;
+use nannou::Draw;
+
+use crate::display::{display_state, DisplayStateAs};
+use crate::universe::Universe;
+
+use super::{Theme, CELL_STROKE_WIDTH, SUMMARY_HEIGHT, SUMMARY_WIDTH, SUMMARY_Y_OFFSET};
+
+/// Overview of a universe which stopped, either because it halted or because of an error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary {
+    pub state: String,
+    pub ticks: usize,
+    pub non_empty: usize,
+    pub span: usize,
+    pub error: Option<String>,
+}
+
+impl Summary {
+    pub fn new(universe: &Universe, state_as: &DisplayStateAs, error: Option<String>) -> Self {
+        let symbols = universe.tape.all_symbols();
+        let from = symbols.iter().position(|s| !s.is_empty());
+        let to = symbols.iter().rposition(|s| !s.is_empty());
+
+        Summary {
+            state: display_state(universe.machine.state, state_as),
+            ticks: universe.ticks,
+            non_empty: universe.tape.non_empty(),
+            span: from.zip(to).map(|(from, to)| to - from + 1).unwrap_or(0),
+            error,
+        }
+    }
+}
+
+pub fn draw_summary(summary: &Summary, theme: Theme, draw: &Draw) {
+    let background = theme.background();
+    draw.rect()
+        .color(srgba(
+            background.red,
+            background.green,
+            background.blue,
+            200,
+        ))
+        .stroke_color(theme.outline())
+        .stroke_weight(CELL_STROKE_WIDTH)
+        .w(SUMMARY_WIDTH)
+        .h(SUMMARY_HEIGHT)
+        .x_y(0_f32, SUMMARY_Y_OFFSET);
+
+    let mut text = format!(
+        "final state: {}\nticks: {}\nnon-empty symbols: {}\ntape span: {}",
+        summary.state, summary.ticks, summary.non_empty, summary.span
+    );
+    if let Some(error) = &summary.error {
+        text.push_str(&format!("\nerror: {error}"));
+    }
+
+    draw.text(text.as_str())
+        .color(theme.text())
+        .w(SUMMARY_WIDTH)
+        .h(SUMMARY_HEIGHT)
+        .x_y(0_f32, SUMMARY_Y_OFFSET)
+        .align_text_middle_y()
+        .center_justify();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::presets::busy_beaver::three_state_busy_beaver;
+    use crate::universe::function::TransitionFunctionBuilder;
+    use crate::universe::machine::State;
+    use crate::universe::Universe;
+
+    use super::Summary;
+
+    #[test]
+    fn summary_of_halted_beaver_3() {
+        let mut beaver = three_state_busy_beaver();
+        while !beaver.universe.machine.state.is_halted() {
+            beaver.universe.tick().unwrap();
+        }
+
+        let summary = Summary::new(&beaver.universe, &beaver.display_state_as, None);
+
+        assert_eq!(summary.state, String::from("!"));
+        assert_eq!(summary.ticks, 14);
+        assert_eq!(summary.non_empty, 6);
+        assert_eq!(summary.span, 6);
+        assert_eq!(summary.error, None);
+    }
+
+    #[test]
+    fn summary_of_empty_universe() {
+        let universe = Universe::new(
+            vec![],
+            0,
+            State::from(0),
+            TransitionFunctionBuilder::default().build(),
+        );
+        let error = universe.clone().tick().unwrap_err();
+
+        let summary = Summary::new(&universe, &HashMap::new(), Some(error.clone()));
+
+        assert_eq!(summary.state, String::from("0"));
+        assert_eq!(summary.non_empty, 0);
+        assert_eq!(summary.span, 0);
+        assert_eq!(summary.error, Some(error));
+    }
+}
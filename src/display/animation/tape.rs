@@ -1,49 +1,121 @@
+use nannou::color::srgba;
+use nannou::geom::pt2;
 use nannou::Draw;
 
-use crate::universe::{tape::Tape, Symbol};
+use crate::display::{display_symbol, DisplaySymbolAs};
+use crate::universe::tape::TapeMode;
+use crate::universe::{Symbol, Universe};
 
 use super::{
-    CELL_HEIGHT, CELL_OUTLINE_COLOR, CELL_STROKE_WIDTH, CELL_WIDTH, CELL_Y_OFFSET,
-    DISPLAY_TAPE_HALF_WIDTH,
+    Theme, CELL_HEIGHT, CELL_STROKE_WIDTH, CELL_WIDTH, CELL_Y_OFFSET, DISPLAY_TAPE_HALF_WIDTH,
 };
 
-pub fn draw_tape(tape: &Tape, pos: isize, offset: isize, draw: &Draw) {
+/// Symbol which is being written to the tape, drawn instead of whatever is on the tape.
+pub struct FadeIn<'a> {
+    pub pos: isize,
+    pub text: &'a str,
+    pub alpha: f32,
+}
+
+/// Draw the cells of the tape which are visible, shifted by the offset, with a marker at each end of the tape.
+pub fn draw_tape(
+    universe: &Universe,
+    symbol_as: &DisplaySymbolAs,
+    offset: f32,
+    fade_in: Option<FadeIn>,
+    theme: Theme,
+    draw: &Draw,
+) {
+    let (tape, pos) = (&universe.tape, universe.pos);
     let draw_range =
         (-(DISPLAY_TAPE_HALF_WIDTH as isize)).min(pos)..(DISPLAY_TAPE_HALF_WIDTH as isize).max(pos);
 
-    for pos in draw_range {
-        draw_cell(pos, draw)
+    // cells which are visible, as the tape is drawn shifted by the offset
+    let (left, right) = universe.tape_mode.bounds();
+    let start = draw_range.start + offset.floor() as isize;
+    let end = draw_range.end + offset.ceil() as isize + 1;
+    let visible =
+        left.map_or(start, |left| start.max(left))..right.map_or(end, |right| end.min(right + 1));
+
+    for pos in visible.clone() {
+        draw_cell(pos as f32 - offset, theme, draw)
+    }
+
+    match universe.tape_mode {
+        TapeMode::Bounded { left, right, .. } => {
+            draw_end_marker(left as f32 - 0.5 - offset, theme, draw);
+            draw_end_marker(right as f32 + 0.5 - offset, theme, draw);
+        }
+        TapeMode::OneWay { left, .. } => draw_end_marker(left as f32 - 0.5 - offset, theme, draw),
+        _ => {}
     }
 
-    let symbols = tape.second_half();
-    for (pos, symbol) in symbols.iter().enumerate() {
-        draw_symbol(symbol, pos as isize + 1 - offset, draw);
+    let fade_pos = fade_in.as_ref().map(|fade_in| fade_in.pos);
+    for (pos, symbol) in visible.clone().zip(tape.symbols(visible)) {
+        if fade_pos != Some(pos) {
+            draw_symbol(&symbol, symbol_as, pos as f32 - offset, 1.0, theme, draw);
+        }
     }
 
-    let symbols = tape.first_half();
-    for (pos, symbol) in symbols.iter().enumerate() {
-        draw_symbol(symbol, -(pos as isize) - offset, draw);
+    if let Some(fade_in) = fade_in {
+        draw_text(
+            fade_in.text,
+            fade_in.pos as f32 - offset,
+            fade_in.alpha,
+            theme,
+            draw,
+        );
     }
 }
 
-pub fn draw_cell(pos: isize, draw: &Draw) {
+pub fn draw_cell(pos: f32, theme: Theme, draw: &Draw) {
     draw.rect()
-        .stroke_color(CELL_OUTLINE_COLOR)
+        .stroke_color(theme.outline())
         .stroke_weight(CELL_STROKE_WIDTH)
         .no_fill()
         .w(CELL_WIDTH)
         .h(CELL_HEIGHT)
-        .x_y(CELL_WIDTH * pos as f32, CELL_Y_OFFSET);
+        .x_y(CELL_WIDTH * pos, CELL_Y_OFFSET);
 }
 
-pub fn draw_symbol(content: &Symbol, pos: isize, draw: &Draw) {
-    let symbol_text = if content.is_empty() {
-        String::default()
-    } else {
-        content.to_string()
-    };
+/// Thick line between two cells, past which the head can not move.
+fn draw_end_marker(pos: f32, theme: Theme, draw: &Draw) {
+    let x = CELL_WIDTH * pos;
+    draw.line()
+        .color(theme.outline())
+        .weight(4.0 * CELL_STROKE_WIDTH)
+        .start(pt2(x, CELL_Y_OFFSET - CELL_HEIGHT))
+        .end(pt2(x, CELL_Y_OFFSET + CELL_HEIGHT));
+}
+
+pub fn draw_symbol(
+    content: &Symbol,
+    symbol_as: &DisplaySymbolAs,
+    pos: f32,
+    alpha: f32,
+    theme: Theme,
+    draw: &Draw,
+) {
+    if !content.is_empty() {
+        draw_text(
+            &display_symbol(*content, symbol_as),
+            pos,
+            alpha,
+            theme,
+            draw,
+        );
+    }
+}
 
-    draw.text(&symbol_text)
-        .x_y(CELL_WIDTH * pos as f32, CELL_Y_OFFSET)
+fn draw_text(text: &str, pos: f32, alpha: f32, theme: Theme, draw: &Draw) {
+    let color = theme.text();
+    draw.text(text)
+        .color(srgba(
+            color.red,
+            color.green,
+            color.blue,
+            (alpha * 255.0) as u8,
+        ))
+        .x_y(CELL_WIDTH * pos, CELL_Y_OFFSET)
         .center_justify();
 }
@@ -1,16 +1,121 @@
-use crate::{display::display_state, presets::UniverseMetadata};
+use std::ops::{ControlFlow, Range};
+use std::path::Path;
 
-pub fn print_machine(busy_beaver_packed: UniverseMetadata) {
+use crate::display::{display_state, display_symbol, display_write, DisplaySymbolAs};
+use crate::presets::registry::{families, presets};
+use crate::presets::UniverseMetadata;
+use crate::universe::check::Issue;
+use crate::universe::equivalence::{compare, Halting, Outcome};
+use crate::universe::tape::{Tape, TapeMode};
+use crate::universe::{Symbol, Universe};
+
+pub fn print_presets() {
+    // (category, name, description) of presets, followed by families
+    let mut lines: Vec<_> = presets()
+        .into_iter()
+        .map(|preset| {
+            let description = if preset.aliases.is_empty() {
+                preset.description.to_owned()
+            } else {
+                let aliases = preset.aliases.join(", ");
+                format!("{} [aliases: {aliases}]", preset.description)
+            };
+            (preset.category, preset.name, description)
+        })
+        .chain(families().into_iter().map(|family| {
+            let description = family.description.to_owned();
+            (family.category, family.pattern, description)
+        }))
+        .collect();
+    lines.sort_by_key(|(category, _, _)| *category);
+
+    let width = lines
+        .iter()
+        .map(|(_, name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    let mut current = None;
+    for (category, name, description) in lines {
+        if current != Some(category) {
+            if current.is_some() {
+                println!();
+            }
+            println!("{category}");
+            current = Some(category);
+        }
+
+        println!("  {name:width$}  {description}");
+    }
+}
+
+/// Print the issues of the machine, which fails if any of them is an error.
+pub fn print_check(universe_meta: &UniverseMetadata) -> Result<(), String> {
+    let display_state_as = &universe_meta.display_state_as;
+    let display_symbol_as = &universe_meta.display_symbol_as;
+    let state = |state| display_state(state, display_state_as);
+    let symbol = |symbol| display_symbol(symbol, display_symbol_as);
+
+    let issues = universe_meta.transition_function_buidler.check(
+        &universe_meta.symbol_set,
+        &universe_meta.state_set,
+        universe_meta.universe.machine.state,
+    );
+    for issue in &issues {
+        let message = match *issue {
+            Issue::DuplicateInput(input) => format!(
+                "({}, {}) has more than one transition, the last one is used",
+                state(input.state),
+                symbol(input.symbol)
+            ),
+            Issue::MissingInput(input) => format!(
+                "({}, {}) has no transition",
+                state(input.state),
+                symbol(input.symbol)
+            ),
+            Issue::Unreachable(s) => format!("{} can not be reached", state(s)),
+            Issue::NoPathToHalt(s) => format!("{} never halts", state(s)),
+            Issue::UndeclaredSymbol(s) => format!("{} is written, but not declared", symbol(s)),
+            Issue::UndeclaredState(s) => format!("{} is used, but not declared", state(s)),
+        };
+        let level = if issue.is_error() { "error" } else { "warning" };
+        println!("{level}: {message}");
+    }
+
+    let errors = issues.iter().filter(|issue| issue.is_error()).count();
+    println!(
+        "{}: {errors} errors, {} warnings",
+        universe_meta.name,
+        issues.len() - errors
+    );
+    match errors {
+        0 => Ok(()),
+        _ => Err(format!("{errors} errors found")),
+    }
+}
+
+/// Print the machine and every step of its computation, followed by statistics of the run if asked for.
+///
+/// With a checkpoint, the universe is saved to its path every so many ticks, from where the run can be resumed.
+pub fn print_machine(
+    busy_beaver_packed: UniverseMetadata,
+    stats: bool,
+    checkpoint: Option<(usize, &Path)>,
+) {
+    let definition = busy_beaver_packed.clone();
     let name = busy_beaver_packed.name;
     let (symbols, states) = (busy_beaver_packed.symbol_set, busy_beaver_packed.state_set);
     let display_state_as = busy_beaver_packed.display_state_as;
+    let display_symbol_as = busy_beaver_packed.display_symbol_as;
     let mut universe = busy_beaver_packed.universe;
     let builder = busy_beaver_packed.transition_function_buidler;
 
     println!("machine: {name}");
     println!(
         "symbols: {}",
-        symbols.iter().map(|s| format!("{s} ")).collect::<String>()
+        symbols
+            .iter()
+            .map(|s| format!("{} ", display_symbol(*s, &display_symbol_as)))
+            .collect::<String>()
     );
     println!(
         "states: {}",
@@ -32,31 +137,194 @@ pub fn print_machine(busy_beaver_packed: UniverseMetadata) {
     println!("  (current state, scanned symbol) -> (print symbol, move tape, next state)");
 
     for (input, output) in builder.added() {
-        let (cur_s, scanned_s) = (display_state(input.state, &display_state_as), input.symbol);
+        let cur_s = display_state(input.state, &display_state_as);
+        let scanned_s = display_symbol(input.symbol, &display_symbol_as);
 
         let next_s = display_state(output.state, &display_state_as);
-        let (print_s, move_h) = (output.write, output.action);
+        let print_s = display_write(output.write, &display_symbol_as);
+        let move_h = output.action;
         println!("  ({cur_s}, {scanned_s}) -> ({print_s}, {move_h}, {next_s})");
     }
 
     println!("\ncomputation");
     println!("sequence :: instr :: HEAD :: tape");
 
-    let mut sequence = 0;
-    while !universe.machine.state.is_halted() {
+    let result = run(&mut universe, |sequence, universe| {
         let state = display_state(universe.machine.state, &display_state_as);
         println!(
-            "{sequence:8} :: {state:^5} :: {:^4} :: {}",
-            universe.pos, universe.tape
+            "{:8} :: {state:^5} :: {:^4} :: {}",
+            universe.ticks,
+            universe.pos,
+            display_tape(universe, &display_symbol_as)
+        );
+
+        match checkpoint {
+            // the configuration a run starts from is already saved, or not worth saving
+            Some((every, path)) if sequence > 0 && universe.ticks % every.max(1) == 0 => {
+                let snapshot = UniverseMetadata {
+                    universe: universe.clone(),
+                    ..definition.clone()
+                };
+                match snapshot.save_checkpoint(path) {
+                    Ok(()) => ControlFlow::Continue(()),
+                    Err(err) => {
+                        println!("error: {err}");
+                        ControlFlow::Break(())
+                    }
+                }
+            }
+            _ => ControlFlow::Continue(()),
+        }
+    });
+    if let Err(err) = result {
+        println!("error: {err}");
+    }
+
+    if stats {
+        println!("\nstatistics");
+        println!("  ticks: {}", universe.ticks);
+        println!("  non-empty cells: {}", universe.tape.non_empty());
+
+        let universe_stats = &universe.stats;
+        match universe_stats
+            .leftmost_head
+            .zip(universe_stats.rightmost_head)
+        {
+            Some((left, right)) => println!(
+                "  cells visited: {} (from {left} to {right})",
+                universe_stats.span()
+            ),
+            None => println!("  cells visited: 0"),
+        }
+        println!("  head reversals: {}", universe_stats.reversals);
+
+        println!("  state visits:");
+        let mut visited: Vec<_> = universe_stats.state_visits.iter().collect();
+        visited.sort_by_key(|(state, _)| {
+            let declared = states.iter().position(|s| s == *state);
+            (
+                declared.unwrap_or(usize::MAX),
+                display_state(**state, &display_state_as),
+            )
+        });
+        for (state, visits) in visited {
+            println!("    {}: {visits}", display_state(*state, &display_state_as));
+        }
+
+        println!("  transition fires:");
+        for (input, _) in builder.added() {
+            let fires = universe_stats.transition_fires.get(&input).unwrap_or(&0);
+            let cur_s = display_state(input.state, &display_state_as);
+            let scanned_s = display_symbol(input.symbol, &display_symbol_as);
+            println!("    ({cur_s}, {scanned_s}): {fires}");
+        }
+    }
+}
+
+/// Print whether both machines behave the same on every input, or how they differ on the first input they do not.
+pub fn print_equivalence(
+    left: &UniverseMetadata,
+    right: &UniverseMetadata,
+    inputs: Vec<Vec<Symbol>>,
+    max_ticks: usize,
+) -> Result<(), String> {
+    let report = compare(&left.universe, &right.universe, inputs, max_ticks);
+
+    let Some(difference) = report.difference else {
+        println!(
+            "{} and {} agree on {} inputs, {} of which ran out of {max_ticks} ticks on both",
+            left.name, right.name, report.compared, report.undecided
         );
+        return Ok(());
+    };
+
+    let labels = &left.display_symbol_as;
+    let input = if difference.input.is_empty() {
+        String::from("(empty)")
+    } else {
+        Tape::from_iter(difference.input)
+            .labelled(labels)
+            .to_string()
+    };
+    println!("input: {input}");
+    for (universe_meta, outcome) in [(left, &difference.left), (right, &difference.right)] {
+        println!(
+            "  {}: {}",
+            universe_meta.name,
+            display_outcome(outcome, &universe_meta.display_symbol_as)
+        );
+    }
+    Err(format!("machines differ after {} inputs", report.compared))
+}
+
+fn display_outcome(outcome: &Outcome, display_symbol_as: &DisplaySymbolAs) -> String {
+    let ticks = outcome.ticks;
+    let halting = match (outcome.halting, &outcome.error) {
+        (Halting::Halted, _) => format!("halted after {ticks} ticks"),
+        (Halting::Failed, Some(err)) => format!("failed after {ticks} ticks ({err})"),
+        (Halting::Failed, None) => format!("failed after {ticks} ticks"),
+        (Halting::Running, _) => format!("still running after {ticks} ticks"),
+    };
+    let written = outcome.written();
+    let tape = if written.is_empty() {
+        String::from("empty")
+    } else {
+        let labelled = outcome
+            .tape
+            .labelled_range(written.clone(), display_symbol_as);
+        format!("{labelled} from {}", written.start)
+    };
+    format!("{halting}, head at {}, tape {tape}", outcome.head)
+}
 
-        universe.tick().unwrap();
+/// Tick the universe until it halts or fails, or `step` breaks, where `step` is called for every configuration,
+/// starting with the initial one, along with its sequence number.
+pub fn run(
+    universe: &mut Universe,
+    mut step: impl FnMut(usize, &Universe) -> ControlFlow<()>,
+) -> Result<(), String> {
+    let mut sequence = 0;
+    if step(sequence, universe).is_break() {
+        return Ok(());
+    }
+
+    while !universe.machine.state.is_halted() {
+        universe.tick()?;
         sequence += 1;
+        if step(sequence, universe).is_break() {
+            break;
+        }
     }
+    Ok(())
+}
 
-    let state = display_state(universe.machine.state, &display_state_as);
-    println!(
-        "{sequence:8} :: {state:^5} :: {:^4} :: {}",
-        universe.pos, universe.tape
-    );
+/// Cells of the tape as shown in the console, after a `|` marker at each end the head can not move past.
+fn display_tape(universe: &Universe, display_symbol_as: &DisplaySymbolAs) -> String {
+    let tape = universe
+        .tape
+        .labelled_range(tape_range(universe), display_symbol_as);
+    match universe.tape_mode {
+        TapeMode::Bounded { .. } => format!("|{tape}|"),
+        TapeMode::OneWay { .. } => format!("|{tape}"),
+        _ => tape.to_string(),
+    }
+}
+
+/// Cells from cell 0 or the leftmost written cell to the rightmost written cell, widened to include the head.
+///
+/// A bounded or circular tape shows all of its cells instead, and a one-way tape starts at its left end.
+fn tape_range(universe: &Universe) -> Range<isize> {
+    let tape = &universe.tape;
+    let leftmost = tape
+        .leftmost_written()
+        .unwrap_or(0)
+        .min(0)
+        .min(universe.pos);
+    let rightmost = tape.rightmost_written().unwrap_or(0).max(universe.pos);
+
+    match universe.tape_mode.bounds() {
+        (Some(left), Some(right)) => left..right + 1,
+        (Some(left), None) => left..rightmost.max(left) + 1,
+        _ => leftmost..rightmost + 1,
+    }
 }
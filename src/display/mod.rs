@@ -1,11 +1,15 @@
 use std::collections::HashMap;
 
-use crate::universe::machine::State;
+use crate::universe::machine::{State, Write};
+use crate::universe::Symbol;
 
+#[cfg(feature = "gui")]
 pub mod animation;
 pub mod cli;
+pub mod plot;
 
 pub type DisplayStateAs = HashMap<State, String>;
+pub type DisplaySymbolAs = HashMap<Symbol, String>;
 
 pub fn display_state(state: State, display_state_as: &DisplayStateAs) -> String {
     match state {
@@ -19,3 +23,18 @@ pub fn display_state(state: State, display_state_as: &DisplayStateAs) -> String
         }
     }
 }
+
+pub fn display_symbol(symbol: Symbol, display_symbol_as: &DisplaySymbolAs) -> String {
+    if let Some(display) = display_symbol_as.get(&symbol) {
+        display.to_owned()
+    } else {
+        format!("{symbol}")
+    }
+}
+
+pub fn display_write(write: Write, display_symbol_as: &DisplaySymbolAs) -> String {
+    match write {
+        Write::Print(symbol) => format!("W({})", display_symbol(symbol, display_symbol_as)),
+        write => write.to_string(),
+    }
+}
@@ -0,0 +1,299 @@
+use std::fs;
+use std::ops::ControlFlow;
+use std::path::Path;
+
+use crate::display::cli::run;
+use crate::presets::UniverseMetadata;
+use crate::universe::Universe;
+
+const PLOT_WIDTH: f32 = 800.0;
+const PLOT_HEIGHT: f32 = 250.0;
+const PLOT_MARGIN: f32 = 50.0;
+/// Most samples kept before every other one is dropped, which is still more than a point per pixel of the plot.
+const MAX_SAMPLES: usize = 2000;
+
+/// Configuration of a universe at some tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    pub tick: usize,
+    pub head: isize,
+    /// Number of cells from the leftmost to the rightmost non-empty cell
+    pub tape_length: usize,
+}
+
+impl From<&Universe> for Sample {
+    fn from(universe: &Universe) -> Self {
+        let tape_length = match universe.tape.non_empty_bounds() {
+            Some((leftmost, rightmost)) => (rightmost - leftmost + 1) as usize,
+            None => 0,
+        };
+
+        Sample {
+            tick: universe.ticks,
+            head: universe.pos,
+            tape_length,
+        }
+    }
+}
+
+/// Samples of every `every` ticks, as well as the last configuration, until the universe stops or after `max_ticks`.
+///
+/// Whenever more than [MAX_SAMPLES] are kept, `every` doubles and every other sample is dropped, so that long runs
+/// are sampled evenly in bounded memory.
+///
+/// The samples are kept when the universe fails, along with why it failed.
+pub fn sample(
+    universe_meta: UniverseMetadata,
+    every: usize,
+    max_ticks: Option<usize>,
+) -> (Vec<Sample>, Result<(), String>) {
+    let mut every = every.max(1);
+    let mut universe = universe_meta.universe;
+    let mut samples = vec![];
+
+    let result = run(&mut universe, |sequence, universe| {
+        if sequence % every == 0 {
+            samples.push(Sample::from(universe));
+            if samples.len() > MAX_SAMPLES {
+                every *= 2;
+                let mut index = 0;
+                samples.retain(|_| {
+                    index += 1;
+                    index % 2 == 1
+                });
+            }
+        }
+        match max_ticks {
+            Some(max_ticks) if sequence >= max_ticks => ControlFlow::Break(()),
+            _ => ControlFlow::Continue(()),
+        }
+    });
+
+    let last = Sample::from(&universe);
+    if samples.last() != Some(&last) {
+        samples.push(last);
+    }
+    (samples, result)
+}
+
+/// Samples as comma separated values, with a header.
+pub fn to_csv(samples: &[Sample]) -> String {
+    let mut csv = String::from("tick,head,tape_length\n");
+    for sample in samples {
+        csv.push_str(&format!(
+            "{},{},{}\n",
+            sample.tick, sample.head, sample.tape_length
+        ));
+    }
+    csv
+}
+
+/// Line plots of the head position and of the tape length over time, one above the other.
+pub fn to_svg(name: &str, samples: &[Sample]) -> String {
+    let height = 2.0 * (PLOT_HEIGHT + PLOT_MARGIN) + PLOT_MARGIN;
+    let width = PLOT_WIDTH + 2.0 * PLOT_MARGIN;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         font-family=\"sans-serif\" font-size=\"12\">\n"
+    );
+    svg.push_str(&format!(
+        "<rect width=\"{width}\" height=\"{height}\" fill=\"white\"/>\n"
+    ));
+
+    let head: Vec<_> = samples.iter().map(|s| (s.tick, s.head as f32)).collect();
+    let tape: Vec<_> = samples
+        .iter()
+        .map(|s| (s.tick, s.tape_length as f32))
+        .collect();
+    svg.push_str(&line_plot(&format!("{name}: head position"), &head, 0.0));
+    svg.push_str(&line_plot(
+        &format!("{name}: tape length"),
+        &tape,
+        PLOT_HEIGHT + PLOT_MARGIN,
+    ));
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Plot with its title above it, and the range of both axes along them.
+fn line_plot(title: &str, points: &[(usize, f32)], y_offset: f32) -> String {
+    let (left, top) = (PLOT_MARGIN, PLOT_MARGIN + y_offset);
+    let last_tick = points.iter().map(|(tick, _)| *tick).max().unwrap_or(0);
+    let min = points.iter().map(|(_, y)| *y).fold(f32::INFINITY, f32::min);
+    let max = points
+        .iter()
+        .map(|(_, y)| *y)
+        .fold(f32::NEG_INFINITY, f32::max);
+    let (min, max) = if points.is_empty() {
+        (0.0, 0.0)
+    } else {
+        (min, max)
+    };
+
+    let x = |tick: usize| left + PLOT_WIDTH * tick as f32 / last_tick.max(1) as f32;
+    let y = |value: f32| top + PLOT_HEIGHT * (1.0 - (value - min) / (max - min).max(1.0));
+    let line: Vec<String> = points
+        .iter()
+        .map(|(tick, value)| format!("{:.1},{:.1}", x(*tick), y(*value)))
+        .collect();
+
+    let (right, bottom) = (left + PLOT_WIDTH, top + PLOT_HEIGHT);
+    let mut plot = format!(
+        "<text x=\"{left}\" y=\"{}\">{}</text>\n",
+        top - 10.0,
+        escape(title)
+    );
+    plot.push_str(&format!(
+        "<rect x=\"{left}\" y=\"{top}\" width=\"{PLOT_WIDTH}\" height=\"{PLOT_HEIGHT}\" fill=\"none\" \
+         stroke=\"gray\"/>\n"
+    ));
+    plot.push_str(&format!(
+        "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{max}</text>\n",
+        left - 5.0,
+        top + 12.0
+    ));
+    plot.push_str(&format!(
+        "<text x=\"{}\" y=\"{bottom}\" text-anchor=\"end\">{min}</text>\n",
+        left - 5.0
+    ));
+    plot.push_str(&format!(
+        "<text x=\"{left}\" y=\"{}\">0</text>\n",
+        bottom + 15.0
+    ));
+    plot.push_str(&format!(
+        "<text x=\"{right}\" y=\"{}\" text-anchor=\"end\">{last_tick} ticks</text>\n",
+        bottom + 15.0
+    ));
+    plot.push_str(&format!(
+        "<polyline points=\"{}\" fill=\"none\" stroke=\"steelblue\"/>\n",
+        line.join(" ")
+    ));
+    plot
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Sample the run and write it to the path, as CSV if it ends in `.csv`, and as SVG otherwise.
+///
+/// A run which fails is plotted up to where it failed, after which its error is returned.
+pub fn save_plot(
+    universe_meta: UniverseMetadata,
+    path: &Path,
+    every: usize,
+    max_ticks: Option<usize>,
+) -> Result<(), String> {
+    let name = universe_meta.name.clone();
+    let (samples, result) = sample(universe_meta, every, max_ticks);
+
+    let content = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("csv") => to_csv(&samples),
+        _ => to_svg(&name, &samples),
+    };
+    fs::write(path, content).map_err(|err| format!("could not save {}: {err}", path.display()))?;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::presets::busy_beaver::{
+        five_state_busy_beaver, four_state_busy_beaver, two_state_busy_beaver,
+    };
+    use crate::universe::tape::{Edge, TapeMode};
+
+    use super::{sample, to_csv, to_svg, Sample, MAX_SAMPLES};
+
+    #[test]
+    fn samples_every_tick() {
+        let samples = sample(two_state_busy_beaver(), 1, None).0;
+        let heads: Vec<_> = samples.iter().map(|s| s.head).collect();
+
+        assert_eq!(heads, vec![2, 3, 2, 1, 0, 1, 2]);
+        assert_eq!(samples.last().map(|s| s.tick), Some(6));
+        assert_eq!(samples.last().map(|s| s.tape_length), Some(4));
+    }
+
+    #[test]
+    fn samples_include_last() {
+        let samples = sample(four_state_busy_beaver(), 10, None).0;
+        let ticks: Vec<_> = samples.iter().map(|s| s.tick).collect();
+
+        assert_eq!(ticks.len(), 12);
+        assert_eq!(ticks[..3], [0, 10, 20]);
+        assert_eq!(ticks.last(), Some(&107));
+    }
+
+    #[test]
+    fn samples_are_thinned_evenly() {
+        let samples = sample(five_state_busy_beaver(), 1, Some(100_000)).0;
+        let ticks: Vec<_> = samples.iter().map(|s| s.tick).collect();
+
+        assert!(ticks.len() <= MAX_SAMPLES + 1);
+        assert_eq!(ticks[..3], [0, 64, 128]);
+        assert!(ticks.windows(2).rev().skip(1).all(|w| w[1] - w[0] == 64));
+        assert_eq!(ticks.last(), Some(&100_000));
+    }
+
+    #[test]
+    fn samples_stop_at_max_ticks() {
+        let samples = sample(four_state_busy_beaver(), 1, Some(5)).0;
+
+        assert_eq!(samples.last().map(|s| s.tick), Some(5));
+    }
+
+    #[test]
+    fn tape_length_counts_non_empty_cells() {
+        let (samples, _) = sample(five_state_busy_beaver(), 1, Some(4));
+        let lengths: Vec<_> = samples.iter().map(|s| s.tape_length).collect();
+
+        assert_eq!(lengths, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn failed_run_keeps_its_samples() {
+        let beaver = two_state_busy_beaver()
+            .with_tape_mode(TapeMode::Bounded {
+                left: 1,
+                right: 3,
+                edge: Edge::Error,
+            })
+            .unwrap();
+        let (samples, result) = sample(beaver, 1, None);
+
+        assert!(result.is_err());
+        assert_eq!(samples.last().map(|s| s.tick), Some(4));
+    }
+
+    #[test]
+    fn csv() {
+        let samples = [
+            Sample {
+                tick: 0,
+                head: 1,
+                tape_length: 0,
+            },
+            Sample {
+                tick: 1,
+                head: -2,
+                tape_length: 3,
+            },
+        ];
+
+        assert_eq!(to_csv(&samples), "tick,head,tape_length\n0,1,0\n1,-2,3\n");
+    }
+
+    #[test]
+    fn svg_has_a_line_per_plot() {
+        let samples = sample(two_state_busy_beaver(), 1, None).0;
+        let svg = to_svg("beaver <2>", &samples);
+
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert!(svg.contains("beaver &lt;2&gt;: head position"));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}
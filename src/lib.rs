@@ -1,3 +1,4 @@
+pub mod compiler;
 pub mod display;
 pub mod presets;
 pub mod universe;
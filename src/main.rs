@@ -1,8 +1,21 @@
-use clap::{command, Parser, Subcommand};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "gui")]
+use std::time::Duration;
 
-use little_turing_machine::display::animation::{animate, AnimateMoving};
-use little_turing_machine::display::cli::print_machine;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use little_turing_machine::compiler::binary::{reduce, BlockEncoding};
+use little_turing_machine::compiler::{brainfuck, language};
+#[cfg(feature = "gui")]
+use little_turing_machine::display::animation::{animate, AnimateMoving, AnimationOptions, Theme};
+use little_turing_machine::display::cli::{
+    print_check, print_equivalence, print_machine, print_presets,
+};
+use little_turing_machine::display::plot::save_plot;
 use little_turing_machine::presets::UniverseMetadata;
+use little_turing_machine::universe::equivalence::inputs_up_to;
+use little_turing_machine::universe::tape::{Edge, TapeMode};
 
 #[derive(Debug, Parser)]
 #[command(about = "Little Turing Machine")]
@@ -11,51 +24,336 @@ struct Cli {
     command: Commands,
 }
 
+/// Which machine to run, either a preset, a machine file or a program
+#[derive(Debug, Args)]
+struct MachineArgs {
+    /// Name of a preset, as listed by list-presets
+    #[arg(short, long, conflicts_with_all = ["file", "program", "brainfuck"])]
+    preset: Option<String>,
+    /// Machine file to load, such as one saved from the editor
+    #[arg(long, conflicts_with_all = ["program", "brainfuck"])]
+    file: Option<PathBuf>,
+    /// Program in the machine language to compile
+    #[arg(long, conflicts_with = "brainfuck")]
+    program: Option<PathBuf>,
+    /// Brainfuck program to compile, whose input is text rather than symbols
+    #[arg(long)]
+    brainfuck: Option<PathBuf>,
+    /// Symbols to put on the tape, starting at cell 1
+    #[arg(long)]
+    input: Option<String>,
+    /// Position of the head, relative to the first symbol of the input
+    #[arg(long, allow_hyphen_values = true)]
+    head: Option<isize>,
+    /// Name of the state to start in
+    #[arg(long)]
+    state: Option<String>,
+    /// Run the equivalent machine over 2 symbols
+    #[arg(long, default_value_t = false)]
+    binary: bool,
+    /// Kind of tape the machine runs on
+    #[arg(long, default_value_t = Tape::default(), value_enum)]
+    tape: Tape,
+    /// Number of cells of a bounded or circular tape, which defaults to up to the end of the input or the head
+    #[arg(long, allow_hyphen_values = true, value_parser = parse_cells)]
+    cells: Option<NonZeroUsize>,
+    /// What happens when the head moves past the end of a bounded or one-way tape
+    #[arg(long, default_value_t = Edge::default(), value_enum)]
+    edge: Edge,
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq, Default)]
+enum Tape {
+    /// Infinite in both directions
+    #[default]
+    Unbounded,
+    /// Limited to the given cells
+    Bounded,
+    /// Limited to the given cells, where each end wraps around to the other
+    Circular,
+    /// Infinite to the right only
+    OneWay,
+}
+
 #[derive(Debug, Subcommand)]
 enum Commands {
+    /// Run a machine and print every tick
     Console {
-        #[arg(short, long)]
-        preset: Option<String>,
+        #[command(flatten)]
+        machine: MachineArgs,
+        /// Print statistics of the run once it stopped
+        #[arg(long, default_value_t = false)]
+        stats: bool,
+        /// Save the run every so many ticks, to resume it later
+        #[arg(long)]
+        checkpoint_every: Option<usize>,
+        /// File to save the run to
+        #[arg(long, default_value = "checkpoint.txt")]
+        checkpoint: PathBuf,
+    },
+    /// Continue a run from its checkpoint, which is updated in place every so many ticks if asked for
+    Resume {
+        /// Checkpoint to continue from
+        file: PathBuf,
+        /// Print statistics of the run once it stopped
+        #[arg(long, default_value_t = false)]
+        stats: bool,
+        /// Save the run every so many ticks, to resume it later
+        #[arg(long)]
+        checkpoint_every: Option<usize>,
     },
+    /// Animate a machine in a new window, where it may be edited
+    #[cfg(feature = "gui")]
     Animate {
-        #[arg(short, long)]
-        preset: Option<String>,
+        #[command(flatten)]
+        machine: MachineArgs,
+        /// Open the window in full screen
         #[arg(short, long, default_value_t = false)]
         full_screen: bool,
+        /// Whether the tape or the machine moves
         #[arg(long, default_value_t=AnimateMoving::default(), value_enum)]
         animate_moving: AnimateMoving,
+        /// Show the number of ticks so far
         #[arg(long, default_value_t = false)]
         show_tick_count: bool,
+        /// Milliseconds per tick
+        #[arg(long, default_value_t = 1000)]
+        tick_speed: u64,
+        /// Colors to draw with
+        #[arg(long, default_value_t=Theme::default(), value_enum)]
+        theme: Theme,
+        /// Width of the window in pixels
+        #[arg(long, requires = "height")]
+        width: Option<u32>,
+        /// Height of the window in pixels
+        #[arg(long, requires = "width")]
+        height: Option<u32>,
+        /// File the editor saves the machine to, by default machine.tm
+        #[arg(long)]
+        save: Option<PathBuf>,
     },
+    /// Plot the head position and tape length over time, as CSV if the output ends in .csv and SVG otherwise
+    Plot {
+        #[command(flatten)]
+        machine: MachineArgs,
+        /// File to write the plot to
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Sample every so many ticks at first, which doubles whenever too many samples are kept
+        #[arg(long, default_value_t = 1)]
+        every: usize,
+        /// Stop after this many ticks, even if the machine did not halt
+        #[arg(long)]
+        max_ticks: Option<usize>,
+    },
+    /// Report duplicate, missing and unused transitions
+    Check {
+        #[command(flatten)]
+        machine: MachineArgs,
+    },
+    /// Run two machines, each a preset or a machine file, on the same inputs and report the first one they differ on
+    Compare {
+        /// First machine, a preset or a machine file
+        left: String,
+        /// Second machine, a preset or a machine file
+        right: String,
+        /// Input to run both machines on, which may be given more than once
+        #[arg(long)]
+        input: Vec<String>,
+        /// Compare every input of at most this many symbols, unless inputs are given
+        #[arg(long, default_value_t = 4, conflicts_with = "input")]
+        max_length: usize,
+        /// Number of ticks after which a run is undecided
+        #[arg(long, default_value_t = 10000)]
+        max_ticks: usize,
+    },
+    /// List the presets and preset families
+    ListPresets,
+}
+
+fn parse_cells(cells: &str) -> Result<NonZeroUsize, String> {
+    cells
+        .parse()
+        .map_err(|_| "expected a number of cells of at least 1".to_string())
+}
+
+impl TryFrom<MachineArgs> for UniverseMetadata {
+    type Error = String;
+
+    fn try_from(mut args: MachineArgs) -> Result<Self, Self::Error> {
+        let universe_meta = match (args.preset, args.file, args.program, args.brainfuck) {
+            (_, _, _, Some(program)) => {
+                let input = args.input.take().unwrap_or_default();
+                brainfuck::load(&program, input.as_bytes())?
+            }
+            (_, _, Some(program), None) => language::load(&program)?,
+            (_, Some(file), None, None) => UniverseMetadata::load(&file)?,
+            (Some(preset), None, None, None) => UniverseMetadata::try_from(preset)?,
+            (None, None, None, None) => UniverseMetadata::default(),
+        };
+
+        let universe_meta = match args.input {
+            Some(input) => universe_meta.with_input(&input)?,
+            None => universe_meta,
+        };
+        let universe_meta = match args.head {
+            Some(head) => universe_meta.with_head(head),
+            None => universe_meta,
+        };
+        let universe_meta = match args.state {
+            Some(state) => universe_meta.with_state(&state)?,
+            None => universe_meta,
+        };
+        // the tape starts at cell 1, or at the head if it starts further left, such as at cell 0
+        let pos = universe_meta.universe.pos;
+        let left = pos.min(1);
+        let right = match args.cells {
+            Some(cells) => isize::try_from(cells.get() - 1)
+                .ok()
+                .and_then(|extra| left.checked_add(extra))
+                .ok_or_else(|| format!("too many cells: {cells}"))?,
+            None => {
+                let tape = &universe_meta.universe.tape;
+                tape.rightmost_written().unwrap_or(left).max(pos)
+            }
+        };
+        let universe_meta = match args.tape {
+            Tape::Unbounded => universe_meta,
+            Tape::Bounded => universe_meta.with_tape_mode(TapeMode::Bounded {
+                left,
+                right,
+                edge: args.edge,
+            })?,
+            Tape::Circular => universe_meta.with_tape_mode(TapeMode::Circular { left, right })?,
+            Tape::OneWay => universe_meta.with_tape_mode(TapeMode::OneWay {
+                left,
+                edge: args.edge,
+            })?,
+        };
+        if args.binary {
+            let encoding = BlockEncoding::new(&universe_meta);
+            return reduce(&universe_meta, &encoding);
+        }
+        Ok(universe_meta)
+    }
+}
+
+/// Machine file at the path if there is one, or the preset of that name otherwise.
+fn load_machine(machine: &str) -> Result<UniverseMetadata, String> {
+    let path = Path::new(machine);
+    if path.is_file() {
+        UniverseMetadata::load(path)
+    } else {
+        UniverseMetadata::try_from(machine.to_owned())
+    }
 }
 
 pub fn main() -> Result<(), String> {
     let args = Cli::parse();
 
     match args.command {
-        Commands::Console { preset } => {
-            let universe_meta = if let Some(preset) = preset {
-                UniverseMetadata::try_from(preset)?
-            } else {
-                UniverseMetadata::default()
-            };
+        Commands::Console {
+            machine,
+            stats,
+            checkpoint_every,
+            checkpoint,
+        } => {
+            let universe_meta = UniverseMetadata::try_from(machine)?;
+            let checkpoint = checkpoint_every.map(|every| (every, checkpoint.as_path()));
 
-            print_machine(universe_meta)
+            print_machine(universe_meta, stats, checkpoint)
         }
+        Commands::Resume {
+            file,
+            stats,
+            checkpoint_every,
+        } => {
+            let universe_meta = UniverseMetadata::load_checkpoint(&file)?;
+            let checkpoint = checkpoint_every.map(|every| (every, file.as_path()));
+
+            print_machine(universe_meta, stats, checkpoint)
+        }
+        #[cfg(feature = "gui")]
         Commands::Animate {
-            preset,
+            machine,
             full_screen,
             animate_moving: move_item,
             show_tick_count,
+            tick_speed,
+            theme,
+            width,
+            height,
+            save,
+        } => {
+            let universe_meta = UniverseMetadata::try_from(machine)?;
+
+            let options = AnimationOptions::default()
+                .tick_speed(Duration::from_millis(tick_speed))
+                .full_screen(full_screen)
+                .animate_moving(move_item)
+                .show_tick_count(show_tick_count)
+                .theme(theme);
+            let options = match width.zip(height) {
+                Some((width, height)) => options.window_size(width, height),
+                None => options,
+            };
+            let options = match save {
+                Some(save) => options.save_path(save),
+                None => options,
+            };
+
+            animate(universe_meta, options)
+        }
+        Commands::Plot {
+            machine,
+            output,
+            every,
+            max_ticks,
+        } => {
+            let universe_meta = UniverseMetadata::try_from(machine)?;
+
+            save_plot(universe_meta, &output, every, max_ticks)?
+        }
+        Commands::Check { machine } => {
+            let universe_meta = UniverseMetadata::try_from(machine)?;
+
+            print_check(&universe_meta)?
+        }
+        Commands::Compare {
+            left,
+            right,
+            input,
+            max_length,
+            max_ticks,
         } => {
-            let universe_meta = if let Some(preset) = preset {
-                UniverseMetadata::try_from(preset)?
+            let (left, right) = (load_machine(&left)?, load_machine(&right)?);
+            let inputs = if input.is_empty() {
+                // only symbols both machines know, as the other symbols are not valid input to one of them
+                let symbols: Vec<_> = left
+                    .symbol_set
+                    .iter()
+                    .filter(|s| !s.is_empty() && right.symbol_set.contains(s))
+                    .copied()
+                    .collect();
+                inputs_up_to(&symbols, max_length)
             } else {
-                UniverseMetadata::default()
+                let mut inputs = vec![];
+                for input in &input {
+                    let symbols = left.parse_input(input)?;
+                    if right.parse_input(input)? != symbols {
+                        return Err(format!(
+                            "input {input} is not the same for {} and {}",
+                            left.name, right.name
+                        ));
+                    }
+                    inputs.push(symbols);
+                }
+                inputs
             };
 
-            animate(universe_meta, None, full_screen, move_item, show_tick_count)
+            print_equivalence(&left, &right, inputs, max_ticks)?
         }
+        Commands::ListPresets => print_presets(),
     }
 
     Ok(())
@@ -0,0 +1,484 @@
+use std::{collections::HashMap, iter::FromIterator};
+
+use crate::universe::function::TransitionFunctionBuilder;
+use crate::universe::machine::{Action::*, State, Write};
+use crate::universe::{Symbol, Universe};
+
+use super::registry::{Category, Preset};
+use super::UniverseMetadata;
+
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        name: "unary_add",
+        aliases: &["add_unary"],
+        description: "Adds two unary numbers separated by a 0",
+        category: Category::Arithmetic,
+        build: unary_addition,
+    },
+    Preset {
+        name: "unary_multiply",
+        aliases: &["multiply_unary"],
+        description: "Multiplies two unary numbers separated by a 0",
+        category: Category::Arithmetic,
+        build: unary_multiplication,
+    },
+    Preset {
+        name: "binary_add",
+        aliases: &["add_binary"],
+        description: "Adds two binary numbers separated by a 2",
+        category: Category::Arithmetic,
+        build: binary_addition,
+    },
+    Preset {
+        name: "binary_to_unary",
+        aliases: &[],
+        description: "Converts a binary number to unary",
+        category: Category::Arithmetic,
+        build: binary_to_unary,
+    },
+    Preset {
+        name: "unary_to_binary",
+        aliases: &[],
+        description: "Converts a unary number to binary",
+        category: Category::Arithmetic,
+        build: unary_to_binary,
+    },
+];
+
+/// Adds `1^m 0 1^n` to `1^(m + n)`, by filling the gap and erasing the last 1.
+pub fn unary_addition() -> UniverseMetadata {
+    let name = String::from("unary addition");
+    let initial_head = 1_usize;
+
+    let se = Symbol::empty();
+    let s0 = Symbol::from(0);
+    let s1 = Symbol::from(1);
+
+    let s_a = State::from(0);
+    let s_b = State::from(1);
+    let s_c = State::from(2);
+    let display_state_as: HashMap<State, String> = HashMap::from_iter([
+        (s_a, "fill".to_owned()),
+        (s_b, "end".to_owned()),
+        (s_c, "erase".to_owned()),
+    ]);
+
+    let mut builder = TransitionFunctionBuilder::default();
+    builder.add(s_a, s1, Write::None, R, s_a);
+    builder.add(s_a, s0, Write::from(s1), R, s_b);
+
+    builder.add(s_b, s1, Write::None, R, s_b);
+    builder.add(s_b, se, Write::None, L, s_c);
+
+    builder.add(s_c, s1, Write::from(se), N, State::halt());
+
+    let transition_function = builder.build();
+    let initial_tape = vec![s1, s1, s1, s0, s1, s1];
+    let universe = Universe::new(initial_tape, initial_head, s_a, transition_function);
+
+    UniverseMetadata {
+        name,
+        head_offset_hint: initial_head,
+        symbol_set: vec![s0, s1],
+        state_set: vec![s_a, s_b, s_c],
+        display_state_as,
+        display_symbol_as: HashMap::new(),
+        transition_function_buidler: builder,
+        universe,
+    }
+}
+
+/// Multiplies `1^m 0 1^n` to `1^(m * n)`.
+///
+/// For every 1 of the first number, marked as 2, every 1 of the second number is marked as 3 and copied behind
+/// a second separator. Finally, everything before the second separator is erased.
+pub fn unary_multiplication() -> UniverseMetadata {
+    let name = String::from("unary multiplication");
+    let initial_head = 1_usize;
+
+    let se = Symbol::empty();
+    let s0 = Symbol::from(0);
+    let s1 = Symbol::from(1);
+    let s_x = Symbol::from(2);
+    let s_y = Symbol::from(3);
+
+    let s_next_m = State::from(0);
+    let s_skip_m = State::from(1);
+    let s_next_n = State::from(2);
+    let s_to_end = State::from(3);
+    let s_append = State::from(4);
+    let s_back = State::from(5);
+    let s_restore = State::from(6);
+    let s_back_m = State::from(7);
+    let s_erase_m = State::from(8);
+    let s_skip = State::from(9);
+    let s_erase_n = State::from(10);
+
+    let display_state_as: HashMap<State, String> = HashMap::from_iter([
+        (s_next_m, "next_m".to_owned()),
+        (s_skip_m, "skip_m".to_owned()),
+        (s_next_n, "next_n".to_owned()),
+        (s_to_end, "to_end".to_owned()),
+        (s_append, "append".to_owned()),
+        (s_back, "back".to_owned()),
+        (s_restore, "restore".to_owned()),
+        (s_back_m, "back_m".to_owned()),
+        (s_erase_m, "erase_m".to_owned()),
+        (s_skip, "skip".to_owned()),
+        (s_erase_n, "erase_n".to_owned()),
+    ]);
+
+    let mut builder = TransitionFunctionBuilder::default();
+    builder.add(s_next_m, s1, Write::from(s_x), R, s_skip_m);
+    builder.add(s_next_m, s0, Write::None, L, s_erase_m);
+
+    builder.add(s_skip_m, s1, Write::None, R, s_skip_m);
+    builder.add(s_skip_m, s0, Write::None, R, s_next_n);
+
+    builder.add(s_next_n, s_y, Write::None, R, s_next_n);
+    builder.add(s_next_n, s1, Write::from(s_y), R, s_to_end);
+    builder.add(s_next_n, s0, Write::None, L, s_restore);
+    builder.add(s_next_n, se, Write::None, L, s_restore);
+
+    // the first copied 1 also writes the second separator
+    builder.add(s_to_end, s1, Write::None, R, s_to_end);
+    builder.add(s_to_end, s0, Write::None, R, s_append);
+    builder.add(s_to_end, se, Write::from(s0), R, s_append);
+
+    builder.add(s_append, s1, Write::None, R, s_append);
+    builder.add(s_append, se, Write::from(s1), L, s_back);
+
+    builder.add(s_back, s1, Write::None, L, s_back);
+    builder.add(s_back, s0, Write::None, L, s_back);
+    builder.add(s_back, s_y, Write::None, R, s_next_n);
+
+    builder.add(s_restore, s_y, Write::from(s1), L, s_restore);
+    builder.add(s_restore, s0, Write::None, L, s_back_m);
+
+    builder.add(s_back_m, s1, Write::None, L, s_back_m);
+    builder.add(s_back_m, s_x, Write::None, R, s_next_m);
+
+    builder.add(s_erase_m, s_x, Write::from(se), L, s_erase_m);
+    builder.add(s_erase_m, se, Write::None, R, s_skip);
+
+    builder.add(s_skip, se, Write::None, R, s_skip);
+    builder.add(s_skip, s0, Write::from(se), R, s_erase_n);
+
+    builder.add(s_erase_n, s1, Write::from(se), R, s_erase_n);
+    builder.add(s_erase_n, s0, Write::from(se), N, State::halt());
+    builder.add(s_erase_n, se, Write::None, N, State::halt());
+
+    let transition_function = builder.build();
+    let initial_tape = vec![s1, s1, s0, s1, s1, s1];
+    let universe = Universe::new(initial_tape, initial_head, s_next_m, transition_function);
+
+    UniverseMetadata {
+        name,
+        head_offset_hint: initial_head,
+        symbol_set: vec![s0, s1, s_x, s_y],
+        state_set: vec![
+            s_next_m, s_skip_m, s_next_n, s_to_end, s_append, s_back, s_restore, s_back_m,
+            s_erase_m, s_skip, s_erase_n,
+        ],
+        display_state_as,
+        display_symbol_as: HashMap::new(),
+        transition_function_buidler: builder,
+        universe,
+    }
+}
+
+/// Adds `a 2 b` to `a + b`, where both are binary numbers.
+///
+/// Decrements `b` and increments `a` until `b` is zero, after which `b` is erased.
+pub fn binary_addition() -> UniverseMetadata {
+    let name = String::from("binary addition");
+    let initial_head = 1_usize;
+
+    let se = Symbol::empty();
+    let s0 = Symbol::from(0);
+    let s1 = Symbol::from(1);
+    let s2 = Symbol::from(2);
+
+    let s_end = State::from(0);
+    let s_dec = State::from(1);
+    let s_to_a = State::from(2);
+    let s_inc = State::from(3);
+    let s_erase = State::from(4);
+
+    let display_state_as: HashMap<State, String> = HashMap::from_iter([
+        (s_end, "end".to_owned()),
+        (s_dec, "decrement".to_owned()),
+        (s_to_a, "to_a".to_owned()),
+        (s_inc, "increment".to_owned()),
+        (s_erase, "erase".to_owned()),
+    ]);
+
+    let mut builder = TransitionFunctionBuilder::default();
+    builder.add(s_end, s0, Write::None, R, s_end);
+    builder.add(s_end, s1, Write::None, R, s_end);
+    builder.add(s_end, s2, Write::None, R, s_end);
+    builder.add(s_end, se, Write::None, L, s_dec);
+
+    // borrowing up to the separator means b was zero
+    builder.add(s_dec, s0, Write::from(s1), L, s_dec);
+    builder.add(s_dec, s1, Write::from(s0), L, s_to_a);
+    builder.add(s_dec, s2, Write::from(se), R, s_erase);
+
+    builder.add(s_to_a, s0, Write::None, L, s_to_a);
+    builder.add(s_to_a, s1, Write::None, L, s_to_a);
+    builder.add(s_to_a, s2, Write::None, L, s_inc);
+
+    builder.add(s_inc, s1, Write::from(s0), L, s_inc);
+    builder.add(s_inc, s0, Write::from(s1), R, s_end);
+    builder.add(s_inc, se, Write::from(s1), R, s_end);
+
+    builder.add(s_erase, s1, Write::from(se), R, s_erase);
+    builder.add(s_erase, se, Write::None, N, State::halt());
+
+    let transition_function = builder.build();
+    let initial_tape = vec![s1, s0, s1, s2, s1, s1];
+    let universe = Universe::new(initial_tape, initial_head, s_end, transition_function);
+
+    UniverseMetadata {
+        name,
+        head_offset_hint: initial_head,
+        symbol_set: vec![s0, s1, s2],
+        state_set: vec![s_end, s_dec, s_to_a, s_inc, s_erase],
+        display_state_as,
+        display_symbol_as: HashMap::new(),
+        transition_function_buidler: builder,
+        universe,
+    }
+}
+
+/// Converts a binary number to unary.
+///
+/// Writes a separator 2 behind the number, then decrements the number and appends a 1 behind the separator until
+/// the number is zero, after which the number and separator are erased.
+pub fn binary_to_unary() -> UniverseMetadata {
+    let name = String::from("binary to unary");
+    let initial_head = 1_usize;
+
+    let se = Symbol::empty();
+    let s0 = Symbol::from(0);
+    let s1 = Symbol::from(1);
+    let s2 = Symbol::from(2);
+
+    let s_init = State::from(0);
+    let s_dec = State::from(1);
+    let s_to_sep = State::from(2);
+    let s_append = State::from(3);
+    let s_back = State::from(4);
+    let s_erase = State::from(5);
+
+    let display_state_as: HashMap<State, String> = HashMap::from_iter([
+        (s_init, "init".to_owned()),
+        (s_dec, "decrement".to_owned()),
+        (s_to_sep, "to_sep".to_owned()),
+        (s_append, "append".to_owned()),
+        (s_back, "back".to_owned()),
+        (s_erase, "erase".to_owned()),
+    ]);
+
+    let mut builder = TransitionFunctionBuilder::default();
+    builder.add(s_init, s0, Write::None, R, s_init);
+    builder.add(s_init, s1, Write::None, R, s_init);
+    builder.add(s_init, se, Write::from(s2), L, s_dec);
+
+    // borrowing past the most significant digit means the number was zero
+    builder.add(s_dec, s0, Write::from(s1), L, s_dec);
+    builder.add(s_dec, s1, Write::from(s0), R, s_to_sep);
+    builder.add(s_dec, se, Write::None, R, s_erase);
+
+    builder.add(s_to_sep, s0, Write::None, R, s_to_sep);
+    builder.add(s_to_sep, s1, Write::None, R, s_to_sep);
+    builder.add(s_to_sep, s2, Write::None, R, s_append);
+
+    builder.add(s_append, s1, Write::None, R, s_append);
+    builder.add(s_append, se, Write::from(s1), L, s_back);
+
+    builder.add(s_back, s1, Write::None, L, s_back);
+    builder.add(s_back, s2, Write::None, L, s_dec);
+
+    builder.add(s_erase, s1, Write::from(se), R, s_erase);
+    builder.add(s_erase, s2, Write::from(se), N, State::halt());
+
+    let transition_function = builder.build();
+    let initial_tape = vec![s1, s0, s1];
+    let universe = Universe::new(initial_tape, initial_head, s_init, transition_function);
+
+    UniverseMetadata {
+        name,
+        head_offset_hint: initial_head,
+        symbol_set: vec![s0, s1, s2],
+        state_set: vec![s_init, s_dec, s_to_sep, s_append, s_back, s_erase],
+        display_state_as,
+        display_symbol_as: HashMap::new(),
+        transition_function_buidler: builder,
+        universe,
+    }
+}
+
+/// Converts a unary number to binary.
+///
+/// Writes a separator 2 in front of the number, then erases the last 1 and increments a binary number in front of
+/// the separator until no 1 remains, after which the separator is erased.
+pub fn unary_to_binary() -> UniverseMetadata {
+    let name = String::from("unary to binary");
+    let initial_head = 1_usize;
+
+    let se = Symbol::empty();
+    let s0 = Symbol::from(0);
+    let s1 = Symbol::from(1);
+    let s2 = Symbol::from(2);
+
+    let s_init = State::from(0);
+    let s_sep = State::from(1);
+    let s_end = State::from(2);
+    let s_take = State::from(3);
+    let s_back = State::from(4);
+    let s_inc = State::from(5);
+
+    let display_state_as: HashMap<State, String> = HashMap::from_iter([
+        (s_init, "init".to_owned()),
+        (s_sep, "separate".to_owned()),
+        (s_end, "end".to_owned()),
+        (s_take, "take".to_owned()),
+        (s_back, "back".to_owned()),
+        (s_inc, "increment".to_owned()),
+    ]);
+
+    let mut builder = TransitionFunctionBuilder::default();
+    builder.add(s_init, s1, Write::None, L, s_sep);
+    builder.add(s_init, se, Write::from(s0), N, State::halt());
+
+    builder.add(s_sep, se, Write::from(s2), R, s_end);
+
+    builder.add(s_end, s0, Write::None, R, s_end);
+    builder.add(s_end, s1, Write::None, R, s_end);
+    builder.add(s_end, s2, Write::None, R, s_end);
+    builder.add(s_end, se, Write::None, L, s_take);
+
+    builder.add(s_take, s1, Write::from(se), L, s_back);
+    builder.add(s_take, s2, Write::from(se), N, State::halt());
+
+    builder.add(s_back, s1, Write::None, L, s_back);
+    builder.add(s_back, s2, Write::None, L, s_inc);
+
+    builder.add(s_inc, s1, Write::from(s0), L, s_inc);
+    builder.add(s_inc, s0, Write::from(s1), R, s_end);
+    builder.add(s_inc, se, Write::from(s1), R, s_end);
+
+    let transition_function = builder.build();
+    let initial_tape = vec![s1, s1, s1, s1, s1];
+    let universe = Universe::new(initial_tape, initial_head, s_init, transition_function);
+
+    UniverseMetadata {
+        name,
+        head_offset_hint: initial_head,
+        symbol_set: vec![s0, s1, s2],
+        state_set: vec![s_init, s_sep, s_end, s_take, s_back, s_inc],
+        display_state_as,
+        display_symbol_as: HashMap::new(),
+        transition_function_buidler: builder,
+        universe,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::presets::UniverseMetadata;
+
+    use super::{
+        binary_addition, binary_to_unary, unary_addition, unary_multiplication, unary_to_binary,
+    };
+
+    const MAX_TICKS: usize = 100_000;
+
+    /// Run until halted, and return the tape without surrounding empty symbols.
+    fn run(universe_meta: UniverseMetadata, input: &str) -> String {
+        let mut universe = universe_meta
+            .with_input(input)
+            .unwrap()
+            .with_head(1)
+            .universe;
+        while !universe.machine.state.is_halted() {
+            assert!(universe.ticks < MAX_TICKS, "did not halt for {input}");
+            universe.tick().unwrap();
+        }
+
+        universe.tape.to_string().trim_matches('_').to_owned()
+    }
+
+    fn unary(n: usize) -> String {
+        "1".repeat(n)
+    }
+
+    #[test_case(0, 0)]
+    #[test_case(0, 3)]
+    #[test_case(2, 0)]
+    #[test_case(3, 2)]
+    #[test_case(5, 7)]
+    fn unary_addition_adds(m: usize, n: usize) {
+        let input = format!("{}0{}", unary(m), unary(n));
+
+        assert_eq!(run(unary_addition(), &input), unary(m + n));
+    }
+
+    #[test_case(0, 3)]
+    #[test_case(2, 0)]
+    #[test_case(1, 1)]
+    #[test_case(2, 3)]
+    #[test_case(4, 5)]
+    fn unary_multiplication_multiplies(m: usize, n: usize) {
+        let input = format!("{}0{}", unary(m), unary(n));
+
+        assert_eq!(run(unary_multiplication(), &input), unary(m * n));
+    }
+
+    #[test_case(0, 0)]
+    #[test_case(5, 0)]
+    #[test_case(0, 6)]
+    #[test_case(5, 3)]
+    #[test_case(13, 27)]
+    #[test_case(255, 1)]
+    fn binary_addition_adds(a: usize, b: usize) {
+        let input = format!("{a:b}2{b:b}");
+
+        assert_eq!(run(binary_addition(), &input), format!("{:b}", a + b));
+    }
+
+    #[test_case(0)]
+    #[test_case(1)]
+    #[test_case(5)]
+    #[test_case(8)]
+    #[test_case(13)]
+    fn binary_to_unary_converts(n: usize) {
+        assert_eq!(run(binary_to_unary(), &format!("{n:b}")), unary(n));
+    }
+
+    #[test_case(0)]
+    #[test_case(1)]
+    #[test_case(5)]
+    #[test_case(8)]
+    #[test_case(13)]
+    fn unary_to_binary_converts(n: usize) {
+        assert_eq!(run(unary_to_binary(), &unary(n)), format!("{n:b}"));
+    }
+
+    #[test]
+    fn default_inputs() {
+        let run_default = |universe_meta: UniverseMetadata| {
+            let input = universe_meta.universe.tape.to_string();
+            run(universe_meta, input.trim_matches('_'))
+        };
+
+        assert_eq!(run_default(unary_addition()), unary(5));
+        assert_eq!(run_default(unary_multiplication()), unary(6));
+        assert_eq!(run_default(binary_addition()), String::from("1000"));
+        assert_eq!(run_default(binary_to_unary()), unary(5));
+        assert_eq!(run_default(unary_to_binary()), String::from("101"));
+    }
+}
@@ -4,10 +4,49 @@ use crate::universe::function::TransitionFunctionBuilder;
 use crate::universe::machine::{Action::*, State, Write};
 use crate::universe::{Symbol, Universe};
 
+use super::registry::{Category, Preset};
 use super::UniverseMetadata;
 
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        name: "beaver_1",
+        aliases: &["bb1"],
+        description: "A 1-state 2-symbol busy beaver",
+        category: Category::BusyBeaver,
+        build: one_state_busy_beaver,
+    },
+    Preset {
+        name: "beaver_2",
+        aliases: &["bb2"],
+        description: "A 2-state 2-symbol busy beaver",
+        category: Category::BusyBeaver,
+        build: two_state_busy_beaver,
+    },
+    Preset {
+        name: "beaver_3",
+        aliases: &["bb3"],
+        description: "A 3-state 2-symbol busy beaver",
+        category: Category::BusyBeaver,
+        build: three_state_busy_beaver,
+    },
+    Preset {
+        name: "beaver_4",
+        aliases: &["bb4"],
+        description: "A 4-state 2-symbol busy beaver",
+        category: Category::BusyBeaver,
+        build: four_state_busy_beaver,
+    },
+    Preset {
+        name: "beaver_5",
+        aliases: &["bb5"],
+        description: "A 5-state 2-symbol busy beaver",
+        category: Category::BusyBeaver,
+        build: five_state_busy_beaver,
+    },
+];
+
 pub fn one_state_busy_beaver() -> UniverseMetadata {
-    let name = String::from("2-state, 2-symbol busy beaver");
+    let name = String::from("1-state, 2-symbol busy beaver");
     let initial_head = 0_usize;
 
     let s0 = Symbol::empty();
@@ -29,6 +68,7 @@ pub fn one_state_busy_beaver() -> UniverseMetadata {
         symbol_set: vec![s0, s1],
         state_set: vec![s_a],
         display_state_as,
+        display_symbol_as: HashMap::new(),
         transition_function_buidler: builder,
         universe,
     }
@@ -63,6 +103,7 @@ pub fn two_state_busy_beaver() -> UniverseMetadata {
         symbol_set: vec![s0, s1],
         state_set: vec![s_a, s_b],
         display_state_as,
+        display_symbol_as: HashMap::new(),
         transition_function_buidler: builder,
         universe,
     }
@@ -104,6 +145,7 @@ pub fn three_state_busy_beaver() -> UniverseMetadata {
         symbol_set: vec![s0, s1],
         state_set: vec![s_a, s_b, s_c],
         display_state_as,
+        display_symbol_as: HashMap::new(),
         transition_function_buidler: builder,
         universe,
     }
@@ -148,8 +190,9 @@ pub fn four_state_busy_beaver() -> UniverseMetadata {
         name,
         head_offset_hint: initial_head,
         symbol_set: vec![s0, s1],
-        state_set: vec![s_a, s_b, s_c],
+        state_set: vec![s_a, s_b, s_c, s_d],
         display_state_as,
+        display_symbol_as: HashMap::new(),
         transition_function_buidler: builder,
         universe,
     }
@@ -199,8 +242,9 @@ pub fn five_state_busy_beaver() -> UniverseMetadata {
         name,
         head_offset_hint: initial_head,
         symbol_set: vec![s0, s1],
-        state_set: vec![s_a, s_b, s_c],
+        state_set: vec![s_a, s_b, s_c, s_d, s_e],
         display_state_as,
+        display_symbol_as: HashMap::new(),
         transition_function_buidler: builder,
         universe,
     }
@@ -229,7 +273,7 @@ mod tests {
 
     #[test]
     fn verify_two_state_busy_beaver() {
-        let (s0, s1) = (Symbol::empty(), Symbol::from(1));
+        let s1 = Symbol::from(1);
         let mut beaver = two_state_busy_beaver();
 
         macro_rules! verify_symbols {
@@ -240,10 +284,10 @@ mod tests {
         }
 
         verify_symbols!([]);
-        verify_symbols!([s0, s0, s1]);
-        verify_symbols!([s0, s0, s1, s1]);
-        verify_symbols!([s0, s0, s1, s1]);
-        verify_symbols!([s0, s1, s1, s1]);
+        verify_symbols!([s1]);
+        verify_symbols!([s1, s1]);
+        verify_symbols!([s1, s1]);
+        verify_symbols!([s1, s1, s1]);
         verify_symbols!([s1, s1, s1, s1]);
     }
 
@@ -260,11 +304,11 @@ mod tests {
         }
 
         verify_symbols!([]);
-        verify_symbols!([s0, s1]);
-        verify_symbols!([s0, s1, s0]);
-        verify_symbols!([s0, s1, s0, s1]);
-        verify_symbols!([s0, s1, s1, s1]);
-        verify_symbols!([s0, s1, s1, s1]);
+        verify_symbols!([s1]);
+        verify_symbols!([s1, s0]);
+        verify_symbols!([s1, s0, s1]);
+        verify_symbols!([s1, s1, s1]);
+        verify_symbols!([s1, s1, s1]);
         verify_symbols!([s1, s1, s1, s1]);
         verify_symbols!([s1, s1, s1, s1]);
         verify_symbols!([s1, s1, s1, s1]);
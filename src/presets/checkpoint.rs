@@ -0,0 +1,298 @@
+use std::fs;
+use std::path::Path;
+
+use crate::universe::machine::Write;
+use crate::universe::tape::{Edge, Tape, TapeMode};
+
+use super::file::{parse_action, tape_with_start};
+use super::UniverseMetadata;
+
+/// Snapshot of a universe during its run, from which it continues exactly where it stopped.
+///
+/// ```text
+/// checkpoint
+/// ticks: 6
+/// origin: 0
+/// tape mode: unbounded
+/// head range: 0 3
+/// reversals: 2
+/// last move: R
+/// transition fires: 2 1 2 1
+/// machine:
+/// name: 2-state, 2-symbol busy beaver
+/// ...
+/// ```
+///
+/// The machine is written in the machine file format, where `origin` is the position of its first tape cell.
+/// Transition fires are given in the order of the transitions, from which the state visits follow.
+impl UniverseMetadata {
+    pub fn to_checkpoint(&self) -> String {
+        let universe = &self.universe;
+        let stats = &universe.stats;
+        let (origin, _) = tape_with_start(universe);
+
+        let tape_mode = match universe.tape_mode {
+            TapeMode::Unbounded => String::from("unbounded"),
+            TapeMode::Bounded { left, right, edge } => {
+                format!("bounded {left} {right} {}", edge_name(edge))
+            }
+            TapeMode::Circular { left, right } => format!("circular {left} {right}"),
+            TapeMode::OneWay { left, edge } => format!("one-way {left} {}", edge_name(edge)),
+        };
+        let head_range = match stats.leftmost_head.zip(stats.rightmost_head) {
+            Some((left, right)) => format!("{left} {right}"),
+            None => String::from("none"),
+        };
+        let last_move = stats
+            .last_move
+            .map_or(String::from("none"), |action| action.to_string());
+        let fires: Vec<String> = self
+            .transition_function_buidler
+            .added()
+            .iter()
+            .map(|(input, _)| stats.transition_fires.get(input).unwrap_or(&0).to_string())
+            .collect();
+
+        let lines = [
+            String::from("checkpoint"),
+            format!("ticks: {}", universe.ticks),
+            format!("origin: {origin}"),
+            format!("tape mode: {tape_mode}"),
+            format!("head range: {head_range}"),
+            format!("reversals: {}", stats.reversals),
+            format!("last move: {last_move}"),
+            format!("transition fires: {}", fires.join(" "))
+                .trim_end()
+                .to_owned(),
+            String::from("machine:"),
+        ];
+        let mut checkpoint: String = lines.iter().map(|line| format!("{line}\n")).collect();
+        checkpoint.push_str(&self.to_file_format());
+        checkpoint
+    }
+
+    pub fn from_checkpoint(content: &str) -> Result<Self, String> {
+        let (header, machine) = content
+            .split_once("\nmachine:\n")
+            .ok_or_else(|| String::from("expected machine"))?;
+        let mut lines = header.lines().filter(|line| !line.trim().is_empty());
+        if lines.next().map(str::trim) != Some("checkpoint") {
+            return Err(String::from("expected checkpoint"));
+        }
+        let mut field = |key: &str| {
+            let line = lines.next().ok_or_else(|| format!("expected {key}"))?;
+            line.strip_prefix(key)
+                .and_then(|rest| rest.strip_prefix(':'))
+                .map(|rest| rest.trim().to_owned())
+                .ok_or_else(|| format!("expected {key}, was: {line}"))
+        };
+        let number = |value: &str| {
+            value
+                .parse::<isize>()
+                .map_err(|_| format!("invalid number: {value}"))
+        };
+
+        let ticks = field("ticks")?;
+        let ticks = ticks
+            .parse()
+            .map_err(|_| format!("invalid ticks: {ticks}"))?;
+        let origin = number(&field("origin")?)?;
+        let tape_mode = parse_tape_mode(&field("tape mode")?)?;
+        let head_range = field("head range")?;
+        let head_range = match head_range.as_str() {
+            "none" => None,
+            range => match range.split_whitespace().collect::<Vec<_>>()[..] {
+                [left, right] => Some((number(left)?, number(right)?)),
+                _ => return Err(format!("invalid head range: {range}")),
+            },
+        };
+        let reversals = field("reversals")?;
+        let reversals = reversals
+            .parse()
+            .map_err(|_| format!("invalid reversals: {reversals}"))?;
+        let last_move = match field("last move")?.as_str() {
+            "none" => None,
+            action => Some(parse_action(action)?),
+        };
+        let fires = field("transition fires")?;
+        let fires = fires
+            .split_whitespace()
+            .map(|fires| fires.parse().map_err(|_| format!("invalid fires: {fires}")))
+            .collect::<Result<Vec<usize>, _>>()?;
+
+        let mut universe_meta = UniverseMetadata::from_file_format(machine)?;
+        let added = universe_meta.transition_function_buidler.added();
+        if fires.len() != added.len() {
+            return Err(format!(
+                "expected {} transition fires, was {}",
+                added.len(),
+                fires.len()
+            ));
+        }
+
+        // move the tape and head back to where they were
+        let universe = &mut universe_meta.universe;
+        let mut tape = Tape::default();
+        let (start, symbols) = tape_with_start(universe);
+        for (i, symbol) in symbols.into_iter().enumerate() {
+            tape.write(Write::Print(symbol), origin + i as isize);
+        }
+        universe.tape = tape;
+        universe.pos += origin - start;
+        universe.ticks = ticks;
+        universe.tape_mode = tape_mode;
+
+        let stats = &mut universe.stats;
+        for ((input, _), fires) in added.into_iter().zip(fires) {
+            if fires > 0 {
+                stats.transition_fires.insert(input, fires);
+                *stats.state_visits.entry(input.state).or_default() += fires;
+            }
+        }
+        stats.leftmost_head = head_range.map(|(left, _)| left);
+        stats.rightmost_head = head_range.map(|(_, right)| right);
+        stats.reversals = reversals;
+        stats.last_move = last_move;
+
+        universe_meta.head_offset_hint = universe_meta.universe.pos.max(0) as usize;
+        Ok(universe_meta)
+    }
+
+    pub fn save_checkpoint(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_checkpoint())
+            .map_err(|err| format!("could not save checkpoint to {}: {err}", path.display()))
+    }
+
+    pub fn load_checkpoint(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("could not load checkpoint {}: {err}", path.display()))?;
+        UniverseMetadata::from_checkpoint(&content)
+    }
+}
+
+fn edge_name(edge: Edge) -> &'static str {
+    match edge {
+        Edge::Error => "error",
+        Edge::Stay => "stay",
+    }
+}
+
+fn parse_tape_mode(tape_mode: &str) -> Result<TapeMode, String> {
+    let invalid = || format!("invalid tape mode: {tape_mode}");
+    let number = |value: &str| value.parse::<isize>().map_err(|_| invalid());
+    let edge = |value: &str| match value {
+        "error" => Ok(Edge::Error),
+        "stay" => Ok(Edge::Stay),
+        _ => Err(invalid()),
+    };
+
+    match tape_mode.split_whitespace().collect::<Vec<_>>()[..] {
+        ["unbounded"] => Ok(TapeMode::Unbounded),
+        ["bounded", left, right, e] => Ok(TapeMode::Bounded {
+            left: number(left)?,
+            right: number(right)?,
+            edge: edge(e)?,
+        }),
+        ["circular", left, right] => Ok(TapeMode::Circular {
+            left: number(left)?,
+            right: number(right)?,
+        }),
+        ["one-way", left, e] => Ok(TapeMode::OneWay {
+            left: number(left)?,
+            edge: edge(e)?,
+        }),
+        _ => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::display::display_state;
+    use crate::presets::busy_beaver::{four_state_busy_beaver, two_state_busy_beaver};
+    use crate::presets::registry::presets;
+    use crate::presets::UniverseMetadata;
+    use crate::universe::tape::{Edge, TapeMode};
+    use crate::universe::Universe;
+
+    fn run(universe: &mut Universe, ticks: usize) {
+        for _ in 0..ticks {
+            if universe.machine.state.is_halted() {
+                return;
+            }
+            universe.tick().unwrap();
+        }
+    }
+
+    #[test_case(0)]
+    #[test_case(1)]
+    #[test_case(30)]
+    #[test_case(107)]
+    fn resume_continues_exactly(ticks: usize) {
+        let mut beaver = four_state_busy_beaver();
+        run(&mut beaver.universe, ticks);
+
+        let mut resumed = UniverseMetadata::from_checkpoint(&beaver.to_checkpoint()).unwrap();
+        assert_eq!(resumed.universe.pos, beaver.universe.pos);
+        assert_eq!(resumed.universe.ticks, beaver.universe.ticks);
+        assert_eq!(resumed.universe.stats, beaver.universe.stats);
+
+        run(&mut beaver.universe, 200);
+        run(&mut resumed.universe, 200);
+        assert_eq!(resumed.universe.pos, beaver.universe.pos);
+        assert_eq!(resumed.universe.ticks, beaver.universe.ticks);
+        assert_eq!(resumed.universe.stats, beaver.universe.stats);
+        assert_eq!(
+            resumed.universe.machine.state,
+            beaver.universe.machine.state
+        );
+        assert_eq!(
+            resumed.universe.tape.symbols(-20..20),
+            beaver.universe.tape.symbols(-20..20)
+        );
+    }
+
+    #[test_case(TapeMode::Unbounded)]
+    #[test_case(TapeMode::Bounded { left: -1, right: 3, edge: Edge::Stay })]
+    #[test_case(TapeMode::Circular { left: 1, right: 2 })]
+    #[test_case(TapeMode::OneWay { left: 0, edge: Edge::Error })]
+    fn tape_mode_round_trips(tape_mode: TapeMode) {
+        let mut beaver = two_state_busy_beaver().with_head(1);
+        beaver.universe.tape_mode = tape_mode;
+
+        let resumed = UniverseMetadata::from_checkpoint(&beaver.to_checkpoint()).unwrap();
+        assert_eq!(resumed.universe.tape_mode, tape_mode);
+    }
+
+    #[test]
+    fn every_preset_resumes() {
+        for preset in presets() {
+            let mut original = (preset.build)();
+            let checkpoint = original.to_checkpoint();
+            let mut resumed = UniverseMetadata::from_checkpoint(&checkpoint)
+                .unwrap_or_else(|err| panic!("{}: {err}", preset.name));
+            assert_eq!(resumed.to_checkpoint(), checkpoint, "{}", preset.name);
+
+            for _ in 0..50 {
+                let ticked = original.universe.tick();
+                assert_eq!(resumed.universe.tick(), ticked, "{}", preset.name);
+            }
+            assert_eq!(resumed.universe.pos, original.universe.pos);
+            assert_eq!(resumed.universe.tape, original.universe.tape);
+            assert_eq!(
+                display_state(resumed.universe.machine.state, &resumed.display_state_as),
+                display_state(original.universe.machine.state, &original.display_state_as),
+                "{}",
+                preset.name
+            );
+        }
+    }
+
+    #[test]
+    fn machine_file_is_not_a_checkpoint() {
+        let machine = two_state_busy_beaver().to_file_format();
+
+        assert!(UniverseMetadata::from_checkpoint(&machine).is_err());
+    }
+}
@@ -4,8 +4,71 @@ use crate::universe::function::TransitionFunctionBuilder;
 use crate::universe::machine::{Action::*, State, Write};
 use crate::universe::{Symbol, Universe};
 
+use super::registry::{Category, Family, Preset};
 use super::UniverseMetadata;
 
+const MAX_BASE: usize = 36;
+
+pub const PRESETS: &[Preset] = &[Preset {
+    name: "counter_2",
+    aliases: &["counter_binary", "binary_counter"],
+    description: "A 2-symbol (binary) counter",
+    category: Category::Counter,
+    build: counter_binary,
+}];
+
+pub const FAMILIES: &[Family] = &[
+    Family {
+        pattern: "counter_<base>",
+        description: "A counter in the given base, from 2 to 36",
+        category: Category::Counter,
+        build: |name| parse_base(name.strip_prefix("counter_")?).map(counter),
+    },
+    Family {
+        pattern: "decrementer_<base>",
+        description: "Counts down to zero in the given base, from 2 to 36",
+        category: Category::Counter,
+        build: |name| parse_base(name.strip_prefix("decrementer_")?).map(decrementer),
+    },
+    Family {
+        pattern: "counter_<base>_to_<limit>",
+        description: "A counter in the given base which halts once it reaches the limit",
+        category: Category::Counter,
+        build: |name| {
+            let (base, limit) = name.strip_prefix("counter_")?.split_once("_to_")?;
+            Some(bounded_counter(parse_base(base)?, limit.parse().ok()?))
+        },
+    },
+];
+
+fn parse_base(base: &str) -> Option<usize> {
+    base.parse()
+        .ok()
+        .filter(|base| (2..=MAX_BASE).contains(base))
+}
+
+/// Digits of the value in the given base, least significant first.
+///
+/// Zero has no digits.
+fn digits(mut value: usize, base: usize) -> Vec<usize> {
+    let mut digits = vec![];
+    while value > 0 {
+        digits.push(value % base);
+        value /= base;
+    }
+    digits
+}
+
+/// One character per digit, `0` to `9` followed by `a` to `z`.
+fn digit_labels(base: usize) -> HashMap<Symbol, String> {
+    (0..base)
+        .filter_map(|digit| {
+            let label = char::from_digit(digit as u32, MAX_BASE as u32)?;
+            Some((Symbol::from(digit), label.to_string()))
+        })
+        .collect()
+}
+
 pub fn counter_binary() -> UniverseMetadata {
     let name = String::from("binary counting");
     let initial_head = 0_usize;
@@ -38,7 +101,379 @@ pub fn counter_binary() -> UniverseMetadata {
         symbol_set: vec![s0, s1],
         state_set: vec![s_w, s_r],
         display_state_as,
+        display_symbol_as: HashMap::new(),
+        transition_function_buidler: builder,
+        universe,
+    }
+}
+
+/// Counter in the given base, which counts up from whatever number is to the right of the head.
+///
+/// Panics if the base is smaller than 2.
+pub fn counter(base: usize) -> UniverseMetadata {
+    assert!(base >= 2, "base should be at least 2");
+    let name = format!("base {base} counting");
+    let initial_head = 1_usize;
+
+    let se = Symbol::empty();
+    let symbol_set: Vec<Symbol> = (0..base).map(Symbol::from).collect();
+
+    let s_w = State::from(0);
+    let s_r = State::from(1);
+    let display_state_as: HashMap<State, String> =
+        HashMap::from_iter([(s_w, "write".to_owned()), (s_r, "return".to_owned())]);
+
+    let mut builder = TransitionFunctionBuilder::default();
+
+    builder.add(s_w, se, Write::from(Symbol::from(1)), R, s_r);
+    for digit in 0..base {
+        if digit + 1 < base {
+            builder.add(
+                s_w,
+                Symbol::from(digit),
+                Write::from(Symbol::from(digit + 1)),
+                R,
+                s_r,
+            );
+        } else {
+            builder.add(
+                s_w,
+                Symbol::from(digit),
+                Write::from(Symbol::from(0)),
+                L,
+                s_w,
+            );
+        }
+    }
+
+    builder.add(s_r, se, Write::None, L, s_w);
+    for symbol in &symbol_set {
+        builder.add(s_r, *symbol, Write::None, R, s_r);
+    }
+
+    let transition_function = builder.build();
+    let universe = Universe::new(vec![], initial_head, s_r, transition_function);
+
+    UniverseMetadata {
+        name,
+        head_offset_hint: initial_head,
+        symbol_set,
+        state_set: vec![s_w, s_r],
+        display_state_as,
+        display_symbol_as: digit_labels(base),
+        transition_function_buidler: builder,
+        universe,
+    }
+}
+
+/// Counts down to zero in the given base, after which the number is all zeroes.
+///
+/// Starts at the base itself, so `10` in that base.
+/// Panics if the base is smaller than 2.
+pub fn decrementer(base: usize) -> UniverseMetadata {
+    assert!(base >= 2, "base should be at least 2");
+    let name = format!("base {base} counting down");
+    let initial_head = 1_usize;
+
+    let se = Symbol::empty();
+    let symbol_set: Vec<Symbol> = (0..base).map(Symbol::from).collect();
+
+    let s_r = State::from(0);
+    let s_d = State::from(1);
+    let s_z = State::from(2);
+    let display_state_as: HashMap<State, String> = HashMap::from_iter([
+        (s_r, "return".to_owned()),
+        (s_d, "decrement".to_owned()),
+        (s_z, "zero".to_owned()),
+    ]);
+
+    let mut builder = TransitionFunctionBuilder::default();
+
+    builder.add(s_r, se, Write::None, L, s_d);
+    for symbol in &symbol_set {
+        builder.add(s_r, *symbol, Write::None, R, s_r);
+    }
+
+    // borrowing past the most significant digit means we were at zero
+    builder.add(s_d, se, Write::None, R, s_z);
+    builder.add(
+        s_d,
+        Symbol::from(0),
+        Write::from(Symbol::from(base - 1)),
+        L,
+        s_d,
+    );
+    for digit in 1..base {
+        builder.add(
+            s_d,
+            Symbol::from(digit),
+            Write::from(Symbol::from(digit - 1)),
+            R,
+            s_r,
+        );
+    }
+
+    builder.add(s_z, se, Write::None, N, State::halt());
+    for symbol in &symbol_set {
+        builder.add(s_z, *symbol, Write::from(Symbol::from(0)), R, s_z);
+    }
+
+    let transition_function = builder.build();
+    let initial_tape = vec![Symbol::from(1), Symbol::from(0)];
+    let universe = Universe::new(initial_tape, initial_head, s_r, transition_function);
+
+    UniverseMetadata {
+        name,
+        head_offset_hint: initial_head,
+        symbol_set,
+        state_set: vec![s_r, s_d, s_z],
+        display_state_as,
+        display_symbol_as: digit_labels(base),
+        transition_function_buidler: builder,
+        universe,
+    }
+}
+
+/// Counter in the given base which halts once the number to the right of the head equals the limit.
+///
+/// After every increment, the number is compared to the limit digit by digit, from least significant.
+/// Panics if the base is smaller than 2.
+pub fn bounded_counter(base: usize, limit: usize) -> UniverseMetadata {
+    assert!(base >= 2, "base should be at least 2");
+    let name = format!("base {base} counting to {limit}");
+    let initial_head = 1_usize;
+
+    let se = Symbol::empty();
+    let symbol_set: Vec<Symbol> = (0..base).map(Symbol::from).collect();
+    let limit_digits = digits(limit, base);
+
+    let s_w = State::from(0);
+    let s_r = State::from(1);
+    let s_s = State::from(2);
+    let s_c: Vec<State> = (0..=limit_digits.len())
+        .map(|i| State::from(3 + i))
+        .collect();
+
+    let mut display_state_as: HashMap<State, String> = HashMap::from_iter([
+        (s_w, "write".to_owned()),
+        (s_r, "return".to_owned()),
+        (s_s, "seek".to_owned()),
+    ]);
+    for (i, state) in s_c.iter().enumerate() {
+        display_state_as.insert(*state, format!("check{i}"));
+    }
+
+    let mut builder = TransitionFunctionBuilder::default();
+
+    builder.add(s_w, se, Write::from(Symbol::from(1)), R, s_r);
+    for digit in 0..base {
+        if digit + 1 < base {
+            builder.add(
+                s_w,
+                Symbol::from(digit),
+                Write::from(Symbol::from(digit + 1)),
+                R,
+                s_r,
+            );
+        } else {
+            builder.add(
+                s_w,
+                Symbol::from(digit),
+                Write::from(Symbol::from(0)),
+                L,
+                s_w,
+            );
+        }
+    }
+
+    // return to the end of the number before checking it, or before incrementing it
+    builder.add(s_r, se, Write::None, L, s_c[0]);
+    builder.add(s_s, se, Write::None, L, s_w);
+    for symbol in &symbol_set {
+        builder.add(s_r, *symbol, Write::None, R, s_r);
+        builder.add(s_s, *symbol, Write::None, R, s_s);
+    }
+
+    for (i, limit_digit) in limit_digits.iter().enumerate() {
+        builder.add(s_c[i], se, Write::None, R, s_s);
+        for digit in 0..base {
+            let next_state = if digit == *limit_digit {
+                s_c[i + 1]
+            } else {
+                s_s
+            };
+            let action = if digit == *limit_digit { L } else { R };
+            builder.add(s_c[i], Symbol::from(digit), Write::None, action, next_state);
+        }
+    }
+
+    // all digits of the limit matched, so only leading zeroes may remain
+    let s_done = s_c[limit_digits.len()];
+    builder.add(s_done, se, Write::None, N, State::halt());
+    builder.add(s_done, Symbol::from(0), Write::None, L, s_done);
+    for digit in 1..base {
+        builder.add(s_done, Symbol::from(digit), Write::None, R, s_s);
+    }
+
+    let transition_function = builder.build();
+    let universe = Universe::new(vec![], initial_head, s_r, transition_function);
+
+    let mut state_set = vec![s_w, s_r, s_s];
+    state_set.extend(s_c);
+
+    UniverseMetadata {
+        name,
+        head_offset_hint: initial_head,
+        symbol_set,
+        state_set,
+        display_state_as,
+        display_symbol_as: digit_labels(base),
         transition_function_buidler: builder,
         universe,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::presets::UniverseMetadata;
+    use crate::universe::Symbol;
+
+    use super::{bounded_counter, counter, counter_binary, decrementer, digit_labels, digits};
+
+    /// Value of the number on the tape, ignoring surrounding empty symbols.
+    fn value(universe_meta: &UniverseMetadata, base: usize) -> usize {
+        let symbols = universe_meta.universe.tape.all_symbols();
+        symbols
+            .iter()
+            .filter(|s| !s.is_empty())
+            .fold(0, |value, symbol| {
+                let digit = (0..base).find(|d| Symbol::from(*d) == *symbol).unwrap();
+                value * base + digit
+            })
+    }
+
+    /// Tick until the machine leaves its current state and comes back to it.
+    fn tick_until_returned(universe_meta: &mut UniverseMetadata) {
+        let start = universe_meta.universe.machine.state;
+        while universe_meta.universe.machine.state == start {
+            universe_meta.universe.tick().unwrap();
+        }
+        while universe_meta.universe.machine.state != start {
+            universe_meta.universe.tick().unwrap();
+        }
+    }
+
+    #[test_case(0, 10, vec![])]
+    #[test_case(5, 2, vec![1, 0, 1])]
+    #[test_case(255, 16, vec![15, 15])]
+    fn digits_least_significant_first(value: usize, base: usize, expected: Vec<usize>) {
+        assert_eq!(digits(value, base), expected);
+    }
+
+    #[test_case(2)]
+    #[test_case(3)]
+    #[test_case(10)]
+    #[test_case(16)]
+    fn counter_counts_up(base: usize) {
+        let mut counter = counter(base);
+
+        for expected in 1..=3 * base {
+            tick_until_returned(&mut counter);
+            assert_eq!(value(&counter, base), expected);
+        }
+    }
+
+    #[test_case(2, "01")]
+    #[test_case(16, "0123456789abcdef")]
+    #[test_case(36, "0123456789abcdefghijklmnopqrstuvwxyz")]
+    fn digits_have_one_character_labels(base: usize, expected: &str) {
+        let labels = digit_labels(base);
+        let labels: String = (0..base)
+            .map(|d| labels[&Symbol::from(d)].as_str())
+            .collect();
+
+        assert_eq!(labels, expected);
+    }
+
+    #[test]
+    fn counter_16_displays_letters() {
+        let mut counter = counter(16);
+        for _ in 0..0x2f {
+            tick_until_returned(&mut counter);
+        }
+
+        let tape = &counter.universe.tape;
+        assert_eq!(tape.labelled(&counter.display_symbol_as).to_string(), "2f");
+        assert_eq!(counter.parse_input("2f").unwrap(), tape.all_symbols());
+    }
+
+    #[test]
+    fn counter_2_matches_counter_binary() {
+        let (mut generated, mut binary) = (counter(2), counter_binary().with_head(1));
+
+        for _ in 0..100 {
+            generated.universe.tick().unwrap();
+            binary.universe.tick().unwrap();
+        }
+
+        assert_eq!(generated.universe.tape, binary.universe.tape);
+    }
+
+    #[test_case(2)]
+    #[test_case(10)]
+    fn decrementer_halts_at_zero(base: usize) {
+        let mut decrementer = decrementer(base);
+        assert_eq!(value(&decrementer, base), base);
+
+        while !decrementer.universe.machine.state.is_halted() {
+            decrementer.universe.tick().unwrap();
+        }
+
+        assert_eq!(value(&decrementer, base), 0);
+        assert_eq!(
+            decrementer.universe.tape.all_symbols(),
+            [Symbol::from(0), Symbol::from(0)]
+        );
+    }
+
+    #[test]
+    fn decrementer_from_input() {
+        let mut decrementer = decrementer(10).with_input("1 2").unwrap();
+
+        for expected in (0..12).rev() {
+            tick_until_returned(&mut decrementer);
+            assert_eq!(value(&decrementer, 10), expected);
+        }
+    }
+
+    #[test_case(2, 0)]
+    #[test_case(2, 5)]
+    #[test_case(10, 12)]
+    #[test_case(16, 40)]
+    fn bounded_counter_halts_at_limit(base: usize, limit: usize) {
+        let mut counter = bounded_counter(base, limit);
+
+        for _ in 0..100_000 {
+            if counter.universe.machine.state.is_halted() {
+                break;
+            }
+            counter.universe.tick().unwrap();
+        }
+
+        assert!(counter.universe.machine.state.is_halted());
+        assert_eq!(value(&counter, base), limit);
+    }
+
+    #[test]
+    fn bounded_counter_with_leading_zeroes() {
+        let mut counter = bounded_counter(10, 7).with_input("0 0 5").unwrap();
+
+        while !counter.universe.machine.state.is_halted() {
+            counter.universe.tick().unwrap();
+        }
+
+        assert_eq!(value(&counter, 10), 7);
+    }
+}
@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::display::{display_state, display_symbol, display_write};
+use crate::universe::function::TransitionFunctionBuilder;
+use crate::universe::machine::{Action, State, Write};
+use crate::universe::{Symbol, Universe};
+
+use super::UniverseMetadata;
+
+/// Textual representation of a universe, which mirrors the console output.
+///
+/// ```text
+/// name: 2-state, 2-symbol busy beaver
+/// symbols: _ 1
+/// states: A B
+/// state: A
+/// head: 2
+/// tape: 1 1
+/// transitions:
+/// (A, _) -> (W(1), R, B)
+/// (A, 1) -> (W(1), L, B)
+/// ```
+///
+/// State names may not contain whitespace, commas or parentheses.
+/// Every state used by a transition or as the current state is written to `states`, even if it was not declared.
+/// Writes are `W(<symbol>)`, `E` (erase), `D` (delete) or `N` (none).
+/// The head is given relative to the first symbol of the tape, which is at position 1.
+///
+/// Symbols may have a label, such as `1=a`, after which the label is used in the rest of the file.
+/// A label on its own, such as `a`, is given the next unused number.
+/// Labels may not contain whitespace, commas or `=`.
+impl UniverseMetadata {
+    pub fn to_file_format(&self) -> String {
+        let universe = &self.universe;
+        let state = |state: State| display_state(state, &self.display_state_as);
+        let symbol = |symbol: Symbol| display_symbol(symbol, &self.display_symbol_as);
+        let join = |items: Vec<String>| items.join(" ");
+
+        // declared states first, then any other states the machine uses
+        let mut states = self.state_set.clone();
+        let added = self.transition_function_buidler.added();
+        let used = added
+            .iter()
+            .flat_map(|(input, output)| [input.state, output.state])
+            .chain([universe.machine.state]);
+        for used in used {
+            if !used.is_halted() && !states.contains(&used) {
+                states.push(used);
+            }
+        }
+
+        let (start, symbols) = tape_with_start(universe);
+        let mut lines = vec![
+            format!("name: {}", self.name),
+            format!(
+                "symbols: {}",
+                join(
+                    self.symbol_set
+                        .iter()
+                        .map(|s| match self.display_symbol_as.get(s) {
+                            Some(label) => format!("{s}={label}"),
+                            None => s.to_string(),
+                        })
+                        .collect()
+                )
+            ),
+            format!(
+                "states: {}",
+                join(states.iter().map(|s| state(*s)).collect())
+            ),
+            format!("state: {}", state(universe.machine.state)),
+            format!("head: {}", universe.pos - start + 1),
+            format!(
+                "tape: {}",
+                join(symbols.iter().map(|s| symbol(*s)).collect())
+            ),
+            String::from("transitions:"),
+        ];
+
+        for (input, output) in added {
+            lines.push(format!(
+                "({}, {}) -> ({}, {}, {})",
+                state(input.state),
+                symbol(input.symbol),
+                display_write(output.write, &self.display_symbol_as),
+                output.action,
+                state(output.state)
+            ));
+        }
+
+        lines
+            .iter()
+            .map(|line| format!("{}\n", line.trim_end()))
+            .collect()
+    }
+
+    pub fn from_file_format(content: &str) -> Result<Self, String> {
+        let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+        let mut field = |key: &str| {
+            let line = lines.next().ok_or_else(|| format!("expected {key}"))?;
+            line.strip_prefix(key)
+                .and_then(|rest| rest.strip_prefix(':'))
+                .map(|rest| rest.trim().to_owned())
+                .ok_or_else(|| format!("expected {key}, was: {line}"))
+        };
+
+        let name = field("name")?;
+        let (symbol_set, display_symbol_as) = parse_symbol_set(&field("symbols")?)?;
+        let parse_symbol = |symbol: &str| parse_symbol(symbol, &display_symbol_as);
+
+        let state_names = field("states")?;
+        let state_names: Vec<&str> = state_names.split_whitespace().collect();
+        let state_set: Vec<State> = (0..state_names.len()).map(State::from).collect();
+        let display_state_as: HashMap<State, String> = state_set
+            .iter()
+            .zip(&state_names)
+            .map(|(state, name)| (*state, name.to_string()))
+            .collect();
+        let parse_state = |name: &str| match name {
+            "!" => Ok(State::halt()),
+            name => state_names
+                .iter()
+                .position(|n| *n == name)
+                .map(State::from)
+                .ok_or_else(|| format!("unknown state: {name}")),
+        };
+
+        let initial_state = parse_state(&field("state")?)?;
+        let head = field("head")?;
+        let head: isize = head.parse().map_err(|_| format!("invalid head: {head}"))?;
+        let tape = field("tape")?
+            .split_whitespace()
+            .map(parse_symbol)
+            .collect::<Result<Vec<_>, _>>()?;
+        field("transitions")?;
+
+        let mut builder = TransitionFunctionBuilder::default();
+        for line in lines {
+            let invalid = || format!("invalid transition: {line}");
+            let (input, output) = line.split_once("->").ok_or_else(invalid)?;
+            let [state, symbol]: [&str; 2] = split_tuple(input)
+                .and_then(|input| input.try_into().ok())
+                .ok_or_else(invalid)?;
+            let [write, action, next_state]: [&str; 3] = split_tuple(output)
+                .and_then(|output| output.try_into().ok())
+                .ok_or_else(invalid)?;
+
+            builder.add(
+                parse_state(state)?,
+                parse_symbol(symbol)?,
+                parse_write(write, parse_symbol)?,
+                parse_action(action)?,
+                parse_state(next_state)?,
+            );
+        }
+
+        let mut universe = Universe::new(tape, 0, initial_state, builder.build());
+        universe.pos = head;
+
+        Ok(UniverseMetadata {
+            name,
+            head_offset_hint: head.max(0) as usize,
+            symbol_set,
+            state_set,
+            display_state_as,
+            display_symbol_as,
+            transition_function_buidler: builder,
+            universe,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_file_format())
+            .map_err(|err| format!("could not save to {}: {err}", path.display()))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("could not load {}: {err}", path.display()))?;
+        UniverseMetadata::from_file_format(&content)
+    }
+}
+
+/// Symbols on the tape without surrounding empty symbols, and the position of the first one.
+pub(crate) fn tape_with_start(universe: &Universe) -> (isize, Vec<Symbol>) {
+    let tape = &universe.tape;
+    let Some((leftmost, rightmost)) = tape.leftmost_written().zip(tape.rightmost_written()) else {
+        return (1, vec![]);
+    };
+    let symbols = tape.symbols(leftmost..rightmost + 1);
+    let (Some(from), Some(to)) = (
+        symbols.iter().position(|s| !s.is_empty()),
+        symbols.iter().rposition(|s| !s.is_empty()),
+    ) else {
+        return (1, vec![]);
+    };
+
+    (leftmost + from as isize, symbols[from..=to].to_vec())
+}
+
+/// Split `(a, b, c)` into its comma separated items.
+fn split_tuple(tuple: &str) -> Option<Vec<&str>> {
+    let items = tuple.trim().strip_prefix('(')?.strip_suffix(')')?;
+    Some(items.split(',').map(str::trim).collect())
+}
+
+/// Symbols, either a number, a label for the next unused number, or both, such as `1=a`.
+pub(crate) fn parse_symbol_set(
+    symbols: &str,
+) -> Result<(Vec<Symbol>, HashMap<Symbol, String>), String> {
+    let mut next = symbols
+        .split_whitespace()
+        .filter_map(|symbol| symbol.split('=').next()?.parse::<usize>().ok())
+        .max()
+        .map_or(1, |max| max + 1);
+
+    let (mut symbol_set, mut display_symbol_as) = (vec![], HashMap::new());
+    for symbol in symbols.split_whitespace() {
+        let (number, label) = match symbol.split_once('=') {
+            Some((number, label)) => (number.parse()?, Some(label)),
+            None => match symbol.parse() {
+                Ok(number) => (number, None),
+                Err(_) => {
+                    next += 1;
+                    (Symbol::from(next - 1), Some(symbol))
+                }
+            },
+        };
+
+        if let Some(label) = label {
+            display_symbol_as.insert(number, label.to_owned());
+        }
+        symbol_set.push(number);
+    }
+
+    Ok((symbol_set, display_symbol_as))
+}
+
+pub(crate) fn parse_symbol(
+    symbol: &str,
+    display_symbol_as: &HashMap<Symbol, String>,
+) -> Result<Symbol, String> {
+    match display_symbol_as.iter().find(|(_, label)| *label == symbol) {
+        Some((symbol, _)) => Ok(*symbol),
+        None => symbol.parse(),
+    }
+}
+
+fn parse_write(
+    write: &str,
+    parse_symbol: impl Fn(&str) -> Result<Symbol, String>,
+) -> Result<Write, String> {
+    match write {
+        "E" => Ok(Write::Erase),
+        "D" => Ok(Write::Delete),
+        "N" => Ok(Write::None),
+        write => write
+            .strip_prefix("W(")
+            .and_then(|symbol| symbol.strip_suffix(')'))
+            .ok_or_else(|| format!("invalid write: {write}"))
+            .and_then(parse_symbol)
+            .map(Write::Print),
+    }
+}
+
+pub(crate) fn parse_action(action: &str) -> Result<Action, String> {
+    match action {
+        "L" => Ok(Action::L),
+        "R" => Ok(Action::R),
+        "N" => Ok(Action::N),
+        action => Err(format!("invalid action: {action}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::presets::busy_beaver::three_state_busy_beaver;
+    use crate::presets::counter::counter_binary;
+    use crate::presets::recognizer::palindrome;
+    use crate::presets::UniverseMetadata;
+    use crate::universe::Symbol;
+
+    #[test]
+    fn round_trip_beaver_3() {
+        let beaver = three_state_busy_beaver();
+        let content = beaver.to_file_format();
+        let loaded = UniverseMetadata::from_file_format(&content).unwrap();
+
+        assert_eq!(loaded.name, beaver.name);
+        assert_eq!(loaded.to_file_format(), content);
+    }
+
+    #[test]
+    fn undeclared_states_are_written() {
+        let mut beaver = three_state_busy_beaver();
+        beaver.state_set.truncate(1);
+        let loaded = UniverseMetadata::from_file_format(&beaver.to_file_format()).unwrap();
+
+        assert_eq!(loaded.state_set.len(), 3);
+    }
+
+    #[test]
+    fn round_trip_runs_the_same() {
+        let mut counter = counter_binary();
+        for _ in 0..20 {
+            counter.universe.tick().unwrap();
+        }
+
+        let mut loaded = UniverseMetadata::from_file_format(&counter.to_file_format()).unwrap();
+        for _ in 0..20 {
+            counter.universe.tick().unwrap();
+            loaded.universe.tick().unwrap();
+        }
+
+        assert_eq!(loaded.universe.tape, counter.universe.tape);
+        assert_eq!(
+            loaded.universe.tape.read(loaded.universe.pos),
+            counter.universe.tape.read(counter.universe.pos)
+        );
+    }
+
+    #[test]
+    fn file_format_of_beaver_3() {
+        let expected = "name: 3-state, 2-symbol busy beaver
+symbols: _ 1
+states: A B C
+state: A
+head: 1
+tape:
+transitions:
+(A, _) -> (W(1), R, B)
+(A, 1) -> (W(1), R, !)
+(B, _) -> (W(_), R, C)
+(B, 1) -> (W(1), R, B)
+(C, _) -> (W(1), L, C)
+(C, 1) -> (W(1), L, A)
+";
+
+        assert_eq!(three_state_busy_beaver().to_file_format(), expected);
+    }
+
+    #[test]
+    fn round_trip_labels() {
+        let palindrome = palindrome().universe_meta;
+        let content = palindrome.to_file_format();
+        let loaded = UniverseMetadata::from_file_format(&content).unwrap();
+
+        assert!(content.contains("symbols: 1=a 2=b\n"));
+        assert!(content.contains("tape: a b b a\n"));
+        assert_eq!(loaded.display_symbol_as, palindrome.display_symbol_as);
+        assert_eq!(loaded.to_file_format(), content);
+    }
+
+    #[test]
+    fn labels_without_number_are_numbered() {
+        let content = "name: x\nsymbols: _ 1 a #\nstates: A\nstate: A\nhead: 1\ntape: a 1 #\n\
+            transitions:\n(A, a) -> (W(#), R, !)\n";
+        let loaded = UniverseMetadata::from_file_format(content).unwrap();
+        let (s1, s2, s3) = (Symbol::from(1), Symbol::from(2), Symbol::from(3));
+
+        assert_eq!(loaded.symbol_set, vec![Symbol::empty(), s1, s2, s3]);
+        assert_eq!(loaded.universe.tape.symbols(1..4), vec![s2, s1, s3]);
+        assert_eq!(
+            loaded
+                .universe
+                .tape
+                .labelled(&loaded.display_symbol_as)
+                .to_string(),
+            String::from("a1#")
+        );
+    }
+
+    #[test]
+    fn unknown_state_is_error() {
+        let content = "name: x\nsymbols: _ 1\nstates: A\nstate: B\nhead: 0\ntape:\ntransitions:\n";
+
+        assert_eq!(
+            UniverseMetadata::from_file_format(content).unwrap_err(),
+            String::from("unknown state: B")
+        );
+    }
+}
@@ -1,25 +1,34 @@
 use std::collections::HashMap;
 
+use crate::display::display_symbol;
 use crate::universe::function::TransitionFunctionBuilder;
 use crate::universe::machine::State;
+use crate::universe::tape::{Tape, TapeMode};
 use crate::universe::{Symbol, Universe};
 
-use self::busy_beaver::{
-    five_state_busy_beaver, four_state_busy_beaver, three_state_busy_beaver, two_state_busy_beaver,
-};
-use self::counter::counter_binary;
-
+pub mod arithmetic;
 pub mod busy_beaver;
+pub mod checkpoint;
 pub mod counter;
+pub mod file;
+pub mod recognizer;
+pub mod registry;
+pub mod universal;
 
 /// Universe with some metadata for more elegant views.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UniverseMetadata {
     pub name: String,
     pub head_offset_hint: usize,
     pub symbol_set: Vec<Symbol>,
     pub state_set: Vec<State>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::universe::pairs"))]
     pub display_state_as: HashMap<State, String>,
+    /// Labels of symbols which are not shown as their number, such as `a` or `#`
+    #[cfg_attr(feature = "serde", serde(with = "crate::universe::pairs"))]
+    pub display_symbol_as: HashMap<Symbol, String>,
+    #[cfg_attr(feature = "serde", serde(rename = "transitions"))]
     pub transition_function_buidler: TransitionFunctionBuilder,
     pub universe: Universe,
 }
@@ -28,13 +37,208 @@ impl TryFrom<String> for UniverseMetadata {
     type Error = String;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        match value.as_str() {
-            "beaver_2" => Ok(two_state_busy_beaver()),
-            "beaver_3" => Ok(three_state_busy_beaver()),
-            "beaver_4" => Ok(four_state_busy_beaver()),
-            "beaver_5" => Ok(five_state_busy_beaver()),
-            "counter_2" => Ok(counter_binary()),
-            other => Err(format!("unknown preset: {other}")),
+        registry::build(&value)
+    }
+}
+
+impl UniverseMetadata {
+    /// Replace the tape with the given symbols, the first of which is at position 1.
+    ///
+    /// Each character is a symbol, unless the input contains whitespace, in which case symbols are separated by it.
+    /// Symbols are given by their label or number, and should be either empty or part of the symbol set.
+    pub fn with_input(mut self, input: &str) -> Result<Self, String> {
+        self.universe.tape = Tape::from_iter(self.parse_input(input)?);
+        Ok(self)
+    }
+
+    /// Symbols of the input, as given to [UniverseMetadata::with_input].
+    pub fn parse_input(&self, input: &str) -> Result<Vec<Symbol>, String> {
+        let symbols: Result<Vec<Symbol>, _> = if input.contains(char::is_whitespace) {
+            input
+                .split_whitespace()
+                .map(|s| self.symbol_named(s))
+                .collect()
+        } else {
+            input
+                .chars()
+                .map(|c| self.symbol_named(&c.to_string()))
+                .collect()
+        };
+        let symbols = symbols?;
+
+        if let Some(symbol) = symbols
+            .iter()
+            .find(|s| !s.is_empty() && !self.symbol_set.contains(s))
+        {
+            let symbol = display_symbol(*symbol, &self.display_symbol_as);
+            return Err(format!("symbol not in symbol set: {symbol}"));
+        }
+        Ok(symbols)
+    }
+
+    pub fn with_head(mut self, head: isize) -> Self {
+        self.universe.pos = head;
+        self.head_offset_hint = head.max(0) as usize;
+        self
+    }
+
+    /// Restrict the head to the cells of the tape mode, which should include the head.
+    pub fn with_tape_mode(mut self, tape_mode: TapeMode) -> Result<Self, String> {
+        let pos = self.universe.pos;
+        match tape_mode.bounds() {
+            (Some(left), Some(right)) if left > right => {
+                return Err(format!("tape from {left} to {right} has no cells"));
+            }
+            (Some(left), Some(right)) if !(left..=right).contains(&pos) => {
+                return Err(format!(
+                    "head at {pos} is outside the tape from {left} to {right}"
+                ));
+            }
+            (Some(left), None) if pos < left => {
+                return Err(format!(
+                    "head at {pos} is left of the tape starting at {left}"
+                ));
+            }
+            _ => {}
+        }
+
+        self.universe.tape_mode = tape_mode;
+        Ok(self)
+    }
+
+    /// Set the current state by its display name, its number, or `!` for halt.
+    pub fn with_state(mut self, name: &str) -> Result<Self, String> {
+        self.universe.machine.state = self.state_named(name)?;
+        Ok(self)
+    }
+
+    /// Symbol by its label, or as shown by [Symbol]'s [Display](std::fmt::Display) otherwise.
+    pub fn symbol_named(&self, name: &str) -> Result<Symbol, String> {
+        match self.display_symbol_as.iter().find(|(_, n)| *n == name) {
+            Some((symbol, _)) => Ok(*symbol),
+            None => name.parse(),
+        }
+    }
+
+    pub fn state_named(&self, name: &str) -> Result<State, String> {
+        if name == State::halt().to_string() {
+            return Ok(State::halt());
         }
+
+        self.display_state_as
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(state, _)| *state)
+            .or_else(|| name.parse::<usize>().ok().map(State::from))
+            .filter(|state| {
+                self.state_set.contains(state) || self.display_state_as.contains_key(state)
+            })
+            .ok_or_else(|| format!("unknown state: {name}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::universe::machine::State;
+    use crate::universe::tape::{Edge, TapeMode};
+    use crate::universe::Symbol;
+
+    use super::busy_beaver::three_state_busy_beaver;
+    use super::counter::counter_binary;
+    use super::recognizer::palindrome;
+
+    #[test_case("101")]
+    #[test_case("1 0 1")]
+    fn input_replaces_tape(input: &str) {
+        let counter = counter_binary().with_input(input).unwrap();
+        let (s0, s1) = (Symbol::from(0), Symbol::from(1));
+
+        assert_eq!(counter.universe.tape.symbols(1..4), vec![s1, s0, s1]);
+    }
+
+    #[test_case("abba")]
+    #[test_case("a b b a")]
+    #[test_case("1221")]
+    fn input_by_label(input: &str) {
+        let palindrome = palindrome().universe_meta.with_input(input).unwrap();
+        let (s1, s2) = (Symbol::from(1), Symbol::from(2));
+
+        assert_eq!(palindrome.universe.tape.symbols(1..5), vec![s1, s2, s2, s1]);
+    }
+
+    #[test]
+    fn input_symbol_not_in_set_is_error() {
+        let err = three_state_busy_beaver().with_input("1_2").unwrap_err();
+
+        assert_eq!(err, String::from("symbol not in symbol set: 2"));
+    }
+
+    #[test]
+    fn counter_increments_input() {
+        let mut counter = counter_binary().with_input("1011").unwrap().with_head(1);
+
+        // walk to the end of the number, then carry back to the start
+        for _ in 0..9 {
+            counter.universe.tick().unwrap();
+        }
+
+        assert_eq!(counter.universe.tape.to_string(), String::from("1100"));
+    }
+
+    #[test_case("B", State::from(1))]
+    #[test_case("2", State::from(2))]
+    #[test_case("!", State::halt())]
+    fn state_by_name(name: &str, expected: State) {
+        let beaver = three_state_busy_beaver().with_state(name).unwrap();
+
+        assert_eq!(beaver.universe.machine.state, expected);
+    }
+
+    #[test]
+    fn unknown_state_is_error() {
+        assert!(three_state_busy_beaver().with_state("Q").is_err());
+    }
+
+    #[test_case(1, 3, true)]
+    #[test_case(2, 3, true)]
+    #[test_case(3, 3, false)]
+    #[test_case(2, 1, false)]
+    fn tape_mode_includes_head(left: isize, right: isize, valid: bool) {
+        let beaver = three_state_busy_beaver().with_head(2);
+        let tape_mode = TapeMode::Bounded {
+            left,
+            right,
+            edge: Edge::Stay,
+        };
+
+        assert_eq!(beaver.with_tape_mode(tape_mode).is_ok(), valid);
+    }
+
+    #[test_case(1, true)]
+    #[test_case(2, true)]
+    #[test_case(3, false)]
+    fn one_way_tape_includes_head(left: isize, valid: bool) {
+        let beaver = three_state_busy_beaver().with_head(2);
+        let tape_mode = TapeMode::OneWay {
+            left,
+            edge: Edge::Error,
+        };
+
+        assert_eq!(beaver.with_tape_mode(tape_mode).is_ok(), valid);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn metadata_round_trips_through_json() {
+        use super::UniverseMetadata;
+
+        let machine = palindrome().universe_meta.with_input("aba").unwrap();
+        let json = serde_json::to_string(&machine).unwrap();
+        let parsed: UniverseMetadata = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.to_file_format(), machine.to_file_format());
+        assert_eq!(parsed.universe, machine.universe);
     }
 }
@@ -0,0 +1,528 @@
+use std::{collections::HashMap, iter::FromIterator};
+
+use crate::universe::function::TransitionFunctionBuilder;
+use crate::universe::machine::{Action::*, State, Write};
+use crate::universe::{Symbol, Universe};
+
+use super::registry::{Category, Preset};
+use super::UniverseMetadata;
+
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        name: "palindrome",
+        aliases: &[],
+        description: "Accepts palindromes over a and b",
+        category: Category::Language,
+        build: || palindrome().universe_meta,
+    },
+    Preset {
+        name: "anbn",
+        aliases: &["a^nb^n"],
+        description: "Accepts a n times followed by b n times",
+        category: Category::Language,
+        build: || a_n_b_n().universe_meta,
+    },
+    Preset {
+        name: "anbncn",
+        aliases: &["a^nb^nc^n"],
+        description: "Accepts a, b and c, each n times",
+        category: Category::Language,
+        build: || a_n_b_n_c_n().universe_meta,
+    },
+    Preset {
+        name: "parentheses",
+        aliases: &["balanced"],
+        description: "Accepts balanced parentheses",
+        category: Category::Language,
+        build: || balanced_parentheses().universe_meta,
+    },
+    Preset {
+        name: "copy",
+        aliases: &[],
+        description: "Copies w over a and b to w#w",
+        category: Category::Language,
+        build: || copy().universe_meta,
+    },
+];
+
+const MAX_TICKS: usize = 100_000;
+
+/// Machine which halts through either its accepting or its rejecting state.
+#[derive(Debug)]
+pub struct Recognizer {
+    pub universe_meta: UniverseMetadata,
+    pub accept: State,
+    pub reject: State,
+    pub accept_examples: Vec<&'static str>,
+    pub reject_examples: Vec<&'static str>,
+}
+
+impl Recognizer {
+    /// Whether the input is accepted, which is the state the machine was in right before it halted.
+    pub fn accepts(&self, input: &str) -> Result<bool, String> {
+        let universe_meta = self.universe_meta.clone().with_input(input)?;
+        let mut universe = universe_meta.with_head(1).universe;
+
+        let mut last_state = universe.machine.state;
+        while !universe.machine.state.is_halted() {
+            if universe.ticks >= MAX_TICKS {
+                return Err(format!("{input} did not halt within {MAX_TICKS} ticks"));
+            }
+
+            last_state = universe.machine.state;
+            universe.tick()?;
+        }
+
+        match last_state {
+            state if state == self.accept => Ok(true),
+            state if state == self.reject => Ok(false),
+            _ => Err(format!("{input} halted outside of accept or reject")),
+        }
+    }
+}
+
+/// Builds a recognizer, where every (state, symbol) pair without a transition moves to the rejecting state.
+///
+/// Both the accepting and rejecting state halt on any symbol.
+fn recognizer(
+    name: &str,
+    symbols: Vec<(Symbol, &str)>,
+    states: Vec<(State, &str)>,
+    (accept, reject): (State, State),
+    mut builder: TransitionFunctionBuilder,
+    examples: (Vec<&'static str>, Vec<&'static str>),
+    default_input: Vec<Symbol>,
+) -> Recognizer {
+    let initial_head = 1_usize;
+
+    let mut display_state_as: HashMap<State, String> = HashMap::from_iter(
+        states
+            .iter()
+            .map(|(state, name)| (*state, name.to_string())),
+    );
+    display_state_as.insert(accept, "accept".to_owned());
+    display_state_as.insert(reject, "reject".to_owned());
+
+    let symbol_set: Vec<Symbol> = symbols.iter().map(|(symbol, _)| *symbol).collect();
+    let display_symbol_as: HashMap<Symbol, String> = HashMap::from_iter(
+        symbols
+            .iter()
+            .map(|(symbol, label)| (*symbol, label.to_string())),
+    );
+
+    let symbols: Vec<Symbol> = std::iter::once(Symbol::empty())
+        .chain(symbol_set.iter().cloned())
+        .collect();
+    let added: Vec<_> = builder
+        .added()
+        .into_iter()
+        .map(|(input, _)| input)
+        .collect();
+    for (state, _) in &states {
+        for symbol in &symbols {
+            if !added
+                .iter()
+                .any(|i| i.state == *state && i.symbol == *symbol)
+            {
+                builder.add(*state, *symbol, Write::None, N, reject);
+            }
+        }
+    }
+    for state in [accept, reject] {
+        for symbol in &symbols {
+            builder.add(state, *symbol, Write::None, N, State::halt());
+        }
+    }
+
+    let mut state_set: Vec<State> = states.iter().map(|(state, _)| *state).collect();
+    state_set.extend([accept, reject]);
+
+    let transition_function = builder.build();
+    let universe = Universe::new(
+        default_input,
+        initial_head,
+        state_set[0],
+        transition_function,
+    );
+
+    Recognizer {
+        universe_meta: UniverseMetadata {
+            name: name.to_owned(),
+            head_offset_hint: initial_head,
+            symbol_set,
+            state_set,
+            display_state_as,
+            display_symbol_as,
+            transition_function_buidler: builder,
+            universe,
+        },
+        accept,
+        reject,
+        accept_examples: examples.0,
+        reject_examples: examples.1,
+    }
+}
+
+/// Accepts palindromes over a and b.
+///
+/// Erases the first symbol, and checks that the last symbol is the same before erasing it as well.
+pub fn palindrome() -> Recognizer {
+    let se = Symbol::empty();
+    let s_a = Symbol::from(1);
+    let s_b = Symbol::from(2);
+
+    let s_start = State::from(0);
+    let s_has_a = State::from(1);
+    let s_has_b = State::from(2);
+    let s_check_a = State::from(3);
+    let s_check_b = State::from(4);
+    let s_back = State::from(5);
+    let (accept, reject) = (State::from(6), State::from(7));
+
+    let mut builder = TransitionFunctionBuilder::default();
+    builder.add(s_start, se, Write::None, N, accept);
+    builder.add(s_start, s_a, Write::from(se), R, s_has_a);
+    builder.add(s_start, s_b, Write::from(se), R, s_has_b);
+
+    for (s_has, s_check, s_same, s_other) in [
+        (s_has_a, s_check_a, s_a, s_b),
+        (s_has_b, s_check_b, s_b, s_a),
+    ] {
+        builder.add(s_has, s_a, Write::None, R, s_has);
+        builder.add(s_has, s_b, Write::None, R, s_has);
+        builder.add(s_has, se, Write::None, L, s_check);
+
+        // an empty symbol means the first symbol was also the middle one
+        builder.add(s_check, se, Write::None, N, accept);
+        builder.add(s_check, s_same, Write::from(se), L, s_back);
+        builder.add(s_check, s_other, Write::None, N, reject);
+    }
+
+    builder.add(s_back, s_a, Write::None, L, s_back);
+    builder.add(s_back, s_b, Write::None, L, s_back);
+    builder.add(s_back, se, Write::None, R, s_start);
+
+    recognizer(
+        "palindromes",
+        vec![(s_a, "a"), (s_b, "b")],
+        vec![
+            (s_start, "start"),
+            (s_has_a, "has_a"),
+            (s_has_b, "has_b"),
+            (s_check_a, "check_a"),
+            (s_check_b, "check_b"),
+            (s_back, "back"),
+        ],
+        (accept, reject),
+        builder,
+        (
+            vec!["", "a", "b", "aa", "aba", "abba", "baaab", "abbbabbba"],
+            vec!["ab", "ba", "aab", "abaa", "ababaaba"],
+        ),
+        vec![s_a, s_b, s_b, s_a],
+    )
+}
+
+/// Accepts a n times followed by b n times.
+///
+/// Repeatedly marks the first a as X and the first b as Y.
+pub fn a_n_b_n() -> Recognizer {
+    let se = Symbol::empty();
+    let s_a = Symbol::from(1);
+    let s_b = Symbol::from(2);
+    let s_x = Symbol::from(3);
+    let s_y = Symbol::from(4);
+
+    let s_mark_a = State::from(0);
+    let s_find_b = State::from(1);
+    let s_back = State::from(2);
+    let s_verify = State::from(3);
+    let (accept, reject) = (State::from(4), State::from(5));
+
+    let mut builder = TransitionFunctionBuilder::default();
+    builder.add(s_mark_a, se, Write::None, N, accept);
+    builder.add(s_mark_a, s_a, Write::from(s_x), R, s_find_b);
+    builder.add(s_mark_a, s_y, Write::None, R, s_verify);
+
+    builder.add(s_find_b, s_a, Write::None, R, s_find_b);
+    builder.add(s_find_b, s_y, Write::None, R, s_find_b);
+    builder.add(s_find_b, s_b, Write::from(s_y), L, s_back);
+
+    builder.add(s_back, s_a, Write::None, L, s_back);
+    builder.add(s_back, s_y, Write::None, L, s_back);
+    builder.add(s_back, s_x, Write::None, R, s_mark_a);
+
+    builder.add(s_verify, s_y, Write::None, R, s_verify);
+    builder.add(s_verify, se, Write::None, N, accept);
+
+    recognizer(
+        "a^n b^n",
+        vec![(s_a, "a"), (s_b, "b"), (s_x, "X"), (s_y, "Y")],
+        vec![
+            (s_mark_a, "mark_a"),
+            (s_find_b, "find_b"),
+            (s_back, "back"),
+            (s_verify, "verify"),
+        ],
+        (accept, reject),
+        builder,
+        (
+            vec!["", "ab", "aabb", "aaabbb", "aaaaabbbbb"],
+            vec!["a", "b", "ba", "aab", "abb", "abab", "aabbb"],
+        ),
+        vec![s_a, s_a, s_a, s_b, s_b, s_b],
+    )
+}
+
+/// Accepts a, b and c, each n times and in that order.
+///
+/// Repeatedly marks the first a as X, the first b as Y and the first c as Z.
+pub fn a_n_b_n_c_n() -> Recognizer {
+    let se = Symbol::empty();
+    let s_a = Symbol::from(1);
+    let s_b = Symbol::from(2);
+    let s_c = Symbol::from(3);
+    let s_x = Symbol::from(4);
+    let s_y = Symbol::from(5);
+    let s_z = Symbol::from(6);
+
+    let s_mark_a = State::from(0);
+    let s_find_b = State::from(1);
+    let s_find_c = State::from(2);
+    let s_back = State::from(3);
+    let s_verify = State::from(4);
+    let (accept, reject) = (State::from(5), State::from(6));
+
+    let mut builder = TransitionFunctionBuilder::default();
+    builder.add(s_mark_a, se, Write::None, N, accept);
+    builder.add(s_mark_a, s_a, Write::from(s_x), R, s_find_b);
+    builder.add(s_mark_a, s_y, Write::None, R, s_verify);
+
+    builder.add(s_find_b, s_a, Write::None, R, s_find_b);
+    builder.add(s_find_b, s_y, Write::None, R, s_find_b);
+    builder.add(s_find_b, s_b, Write::from(s_y), R, s_find_c);
+
+    builder.add(s_find_c, s_b, Write::None, R, s_find_c);
+    builder.add(s_find_c, s_z, Write::None, R, s_find_c);
+    builder.add(s_find_c, s_c, Write::from(s_z), L, s_back);
+
+    for symbol in [s_a, s_b, s_y, s_z] {
+        builder.add(s_back, symbol, Write::None, L, s_back);
+    }
+    builder.add(s_back, s_x, Write::None, R, s_mark_a);
+
+    builder.add(s_verify, s_y, Write::None, R, s_verify);
+    builder.add(s_verify, s_z, Write::None, R, s_verify);
+    builder.add(s_verify, se, Write::None, N, accept);
+
+    recognizer(
+        "a^n b^n c^n",
+        vec![
+            (s_a, "a"),
+            (s_b, "b"),
+            (s_c, "c"),
+            (s_x, "X"),
+            (s_y, "Y"),
+            (s_z, "Z"),
+        ],
+        vec![
+            (s_mark_a, "mark_a"),
+            (s_find_b, "find_b"),
+            (s_find_c, "find_c"),
+            (s_back, "back"),
+            (s_verify, "verify"),
+        ],
+        (accept, reject),
+        builder,
+        (
+            vec!["", "abc", "aabbcc", "aaabbbccc"],
+            vec!["a", "ab", "acb", "abcabc", "aabbc", "aabccc", "aabbccc"],
+        ),
+        vec![s_a, s_a, s_b, s_b, s_c, s_c],
+    )
+}
+
+/// Accepts balanced parentheses.
+///
+/// Marks the first unmarked ) as X, and the nearest unmarked ( to its left as well.
+/// Once every ) is marked, no ( may be left.
+pub fn balanced_parentheses() -> Recognizer {
+    let se = Symbol::empty();
+    let s_open = Symbol::from(1);
+    let s_close = Symbol::from(2);
+    let s_x = Symbol::from(3);
+
+    let s_find_close = State::from(0);
+    let s_find_open = State::from(1);
+    let s_verify = State::from(2);
+    let (accept, reject) = (State::from(3), State::from(4));
+
+    let mut builder = TransitionFunctionBuilder::default();
+    builder.add(s_find_close, s_open, Write::None, R, s_find_close);
+    builder.add(s_find_close, s_x, Write::None, R, s_find_close);
+    builder.add(s_find_close, s_close, Write::from(s_x), L, s_find_open);
+    builder.add(s_find_close, se, Write::None, L, s_verify);
+
+    builder.add(s_find_open, s_x, Write::None, L, s_find_open);
+    builder.add(s_find_open, s_open, Write::from(s_x), R, s_find_close);
+
+    builder.add(s_verify, s_x, Write::None, L, s_verify);
+    builder.add(s_verify, se, Write::None, N, accept);
+
+    recognizer(
+        "balanced parentheses",
+        vec![(s_open, "("), (s_close, ")"), (s_x, "X")],
+        vec![
+            (s_find_close, "find_close"),
+            (s_find_open, "find_open"),
+            (s_verify, "verify"),
+        ],
+        (accept, reject),
+        builder,
+        (
+            vec!["", "()", "(())", "()()", "(()())", "(()(()))"],
+            vec!["(", ")", ")(", "(()", "())", "())(", "()("],
+        ),
+        vec![s_open, s_open, s_close, s_open, s_close, s_close],
+    )
+}
+
+/// Copies w over a and b to w#w.
+///
+/// Accepts any such w after copying it, and rejects anything which already contains other symbols.
+/// Every symbol of w is marked as A or B while it is being copied.
+pub fn copy() -> Recognizer {
+    let se = Symbol::empty();
+    let s_a = Symbol::from(1);
+    let s_b = Symbol::from(2);
+    let s_hash = Symbol::from(3);
+    let s_x = Symbol::from(4);
+    let s_y = Symbol::from(5);
+
+    let s_init = State::from(0);
+    let s_rewind = State::from(1);
+    let s_next = State::from(2);
+    let s_carry_a = State::from(3);
+    let s_carry_b = State::from(4);
+    let s_return = State::from(5);
+    let (accept, reject) = (State::from(6), State::from(7));
+
+    let mut builder = TransitionFunctionBuilder::default();
+    builder.add(s_init, s_a, Write::None, R, s_init);
+    builder.add(s_init, s_b, Write::None, R, s_init);
+    builder.add(s_init, se, Write::from(s_hash), L, s_rewind);
+
+    builder.add(s_rewind, s_a, Write::None, L, s_rewind);
+    builder.add(s_rewind, s_b, Write::None, L, s_rewind);
+    builder.add(s_rewind, se, Write::None, R, s_next);
+
+    builder.add(s_next, s_a, Write::from(s_x), R, s_carry_a);
+    builder.add(s_next, s_b, Write::from(s_y), R, s_carry_b);
+    builder.add(s_next, s_hash, Write::None, N, accept);
+
+    for (s_carry, symbol) in [(s_carry_a, s_a), (s_carry_b, s_b)] {
+        for skip in [s_a, s_b, s_hash] {
+            builder.add(s_carry, skip, Write::None, R, s_carry);
+        }
+        builder.add(s_carry, se, Write::from(symbol), L, s_return);
+    }
+
+    for skip in [s_a, s_b, s_hash] {
+        builder.add(s_return, skip, Write::None, L, s_return);
+    }
+    builder.add(s_return, s_x, Write::from(s_a), R, s_next);
+    builder.add(s_return, s_y, Write::from(s_b), R, s_next);
+
+    recognizer(
+        "copy",
+        vec![
+            (s_a, "a"),
+            (s_b, "b"),
+            (s_hash, "#"),
+            (s_x, "A"),
+            (s_y, "B"),
+        ],
+        vec![
+            (s_init, "init"),
+            (s_rewind, "rewind"),
+            (s_next, "next"),
+            (s_carry_a, "carry_a"),
+            (s_carry_b, "carry_b"),
+            (s_return, "return"),
+        ],
+        (accept, reject),
+        builder,
+        (
+            vec!["", "a", "b", "ab", "bbaa", "ababa"],
+            vec!["#", "a#", "ab#", "A"],
+        ),
+        vec![s_a, s_b, s_b],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::universe::machine::State;
+
+    use super::{a_n_b_n, a_n_b_n_c_n, balanced_parentheses, copy, palindrome, Recognizer};
+
+    #[test_case(palindrome())]
+    #[test_case(a_n_b_n())]
+    #[test_case(a_n_b_n_c_n())]
+    #[test_case(balanced_parentheses())]
+    #[test_case(copy())]
+    fn examples_are_recognized(recognizer: Recognizer) {
+        for input in &recognizer.accept_examples {
+            assert_eq!(recognizer.accepts(input), Ok(true), "accept {input}");
+        }
+        for input in &recognizer.reject_examples {
+            assert_eq!(recognizer.accepts(input), Ok(false), "reject {input}");
+        }
+    }
+
+    #[test_case(palindrome())]
+    #[test_case(a_n_b_n())]
+    #[test_case(a_n_b_n_c_n())]
+    #[test_case(balanced_parentheses())]
+    #[test_case(copy())]
+    fn default_input_is_accepted(recognizer: Recognizer) {
+        let mut universe = recognizer.universe_meta.universe.clone();
+
+        let mut last_state = State::halt();
+        while !universe.machine.state.is_halted() {
+            last_state = universe.machine.state;
+            universe.tick().unwrap();
+        }
+
+        assert_eq!(last_state, recognizer.accept);
+    }
+
+    #[test_case("", "#")]
+    #[test_case("a", "a#a")]
+    #[test_case("ab", "ab#ab")]
+    #[test_case("bbaa", "bbaa#bbaa")]
+    fn copy_writes_copy(input: &str, expected: &str) {
+        let universe_meta = copy().universe_meta;
+        let labels = universe_meta.display_symbol_as.clone();
+        let mut universe = universe_meta
+            .with_input(input)
+            .unwrap()
+            .with_head(1)
+            .universe;
+
+        while !universe.machine.state.is_halted() {
+            universe.tick().unwrap();
+        }
+
+        assert_eq!(
+            universe
+                .tape
+                .labelled(&labels)
+                .to_string()
+                .trim_matches('_'),
+            expected
+        );
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt::Display;
+
+use super::{arithmetic, busy_beaver, counter, recognizer, universal, UniverseMetadata};
+
+/// A preset which may be loaded by name or by any of its aliases.
+#[derive(Debug, Clone, Copy)]
+pub struct Preset {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub description: &'static str,
+    pub category: Category,
+    pub build: fn() -> UniverseMetadata,
+}
+
+/// A family of presets generated from parameters in their name, such as `counter_<base>`.
+#[derive(Debug, Clone, Copy)]
+pub struct Family {
+    pub pattern: &'static str,
+    pub description: &'static str,
+    pub category: Category,
+    /// Build the preset with the given name, if it belongs to this family.
+    pub build: fn(&str) -> Option<UniverseMetadata>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+    BusyBeaver,
+    Counter,
+    Arithmetic,
+    Language,
+    Universal,
+}
+
+/// All registered presets, in order of category.
+pub fn presets() -> Vec<Preset> {
+    let mut presets: Vec<Preset> = [
+        busy_beaver::PRESETS,
+        counter::PRESETS,
+        arithmetic::PRESETS,
+        recognizer::PRESETS,
+        universal::PRESETS,
+    ]
+    .concat();
+    presets.sort_by_key(|preset| preset.category);
+    presets
+}
+
+/// All registered families, in order of category.
+pub fn families() -> Vec<Family> {
+    let mut families: Vec<Family> = [counter::FAMILIES].concat();
+    families.sort_by_key(|family| family.category);
+    families
+}
+
+/// Build a preset by name or alias, or from a family, suggesting the closest name if there is none.
+pub fn build(name: &str) -> Result<UniverseMetadata, String> {
+    match find(name) {
+        Ok(preset) => Ok((preset.build)()),
+        Err(err) => families()
+            .iter()
+            .find_map(|family| (family.build)(name))
+            .ok_or(err),
+    }
+}
+
+/// Find a preset by name or alias, suggesting the closest name if there is none.
+pub fn find(name: &str) -> Result<Preset, String> {
+    let presets = presets();
+    if let Some(preset) = presets.iter().find(|preset| preset.matches(name)) {
+        return Ok(*preset);
+    }
+
+    let suggestion = presets
+        .iter()
+        .flat_map(|preset| std::iter::once(&preset.name).chain(preset.aliases))
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, candidate)| *distance <= (candidate.len() / 3).max(2))
+        .min_by_key(|(distance, _)| *distance);
+
+    match suggestion {
+        Some((_, candidate)) => Err(format!("unknown preset: {name}, did you mean {candidate}?")),
+        None => Err(format!("unknown preset: {name}")),
+    }
+}
+
+impl Preset {
+    pub fn matches(&self, name: &str) -> bool {
+        self.name == name || self.aliases.contains(&name)
+    }
+}
+
+impl Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Category::BusyBeaver => write!(f, "busy beaver"),
+            Category::Counter => write!(f, "counter"),
+            Category::Arithmetic => write!(f, "arithmetic"),
+            Category::Language => write!(f, "language"),
+            Category::Universal => write!(f, "universal"),
+        }
+    }
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use test_case::test_case;
+
+    use super::{build, edit_distance, find, presets};
+
+    #[test]
+    fn names_and_aliases_are_unique() {
+        let mut seen = HashSet::new();
+        for preset in presets() {
+            for name in std::iter::once(&preset.name).chain(preset.aliases) {
+                assert!(seen.insert(*name), "{name} registered twice");
+            }
+        }
+    }
+
+    #[test]
+    fn every_preset_builds() {
+        for preset in presets() {
+            assert!(!(preset.build)().name.is_empty(), "{}", preset.name);
+        }
+    }
+
+    #[test_case("beaver_1", "beaver_1")]
+    #[test_case("bb3", "beaver_3")]
+    #[test_case("counter_binary", "counter_2")]
+    fn find_by_name_or_alias(name: &str, expected: &str) {
+        assert_eq!(find(name).unwrap().name, expected);
+    }
+
+    #[test_case("beavr_3", "unknown preset: beavr_3, did you mean beaver_3?")]
+    #[test_case("countr_2", "unknown preset: countr_2, did you mean counter_2?")]
+    #[test_case("turtle", "unknown preset: turtle")]
+    fn unknown_preset_suggests(name: &str, expected: &str) {
+        assert_eq!(find(name).unwrap_err(), expected);
+    }
+
+    #[test_case("counter_10", "base 10 counting")]
+    #[test_case("decrementer_16", "base 16 counting down")]
+    #[test_case("counter_3_to_8", "base 3 counting to 8")]
+    fn build_from_family(name: &str, expected: &str) {
+        assert_eq!(build(name).unwrap().name, expected);
+    }
+
+    #[test_case("counter_1")]
+    #[test_case("counter_x")]
+    fn invalid_family_parameters(name: &str) {
+        assert!(build(name).is_err());
+    }
+
+    #[test_case("", "", 0)]
+    #[test_case("kitten", "sitting", 3)]
+    #[test_case("beaver", "beavers", 1)]
+    fn edit_distance_is_levenshtein(a: &str, b: &str, expected: usize) {
+        assert_eq!(edit_distance(a, b), expected);
+    }
+}
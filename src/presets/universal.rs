@@ -0,0 +1,690 @@
+use crate::compiler::Builder;
+use crate::universe::function::TransitionFunctionBuilder;
+use crate::universe::machine::{Action, Action::*, State, Write};
+use crate::universe::tape::Tape;
+use crate::universe::{Symbol, Universe};
+
+use super::busy_beaver::two_state_busy_beaver;
+use super::registry::{Category, Preset};
+use super::UniverseMetadata;
+
+pub const PRESETS: &[Preset] = &[Preset {
+    name: "utm",
+    aliases: &["universal"],
+    description: "A universal Turing machine, simulating an encoded 2-state busy beaver",
+    category: Category::Universal,
+    build: universal_turing_machine,
+}];
+
+// symbols of the universal machine
+const ZERO: Symbol = Symbol::new(0);
+const ONE: Symbol = Symbol::new(1);
+/// Marked zero, for bits which were already visited
+const MARKED_ZERO: Symbol = Symbol::new(2);
+/// Marked one, for bits which were already visited
+const MARKED_ONE: Symbol = Symbol::new(3);
+const RULE: Symbol = Symbol::new(4);
+/// Start of a rule which does not match the register
+const REJECTED: Symbol = Symbol::new(5);
+const COLON: Symbol = Symbol::new(6);
+const HASH: Symbol = Symbol::new(7);
+const CELL: Symbol = Symbol::new(8);
+/// Start of the cell under the simulated head
+const HEAD: Symbol = Symbol::new(9);
+const LEFT: Symbol = Symbol::new(10);
+const RIGHT: Symbol = Symbol::new(11);
+const NONE: Symbol = Symbol::new(12);
+/// Zero of a cell which is being inserted
+const NEW_ZERO: Symbol = Symbol::new(13);
+const START: Symbol = Symbol::new(14);
+
+const SYMBOLS: [Symbol; 15] = [
+    ZERO,
+    ONE,
+    MARKED_ZERO,
+    MARKED_ONE,
+    RULE,
+    REJECTED,
+    COLON,
+    HASH,
+    CELL,
+    HEAD,
+    LEFT,
+    RIGHT,
+    NONE,
+    NEW_ZERO,
+    START,
+];
+
+/// Label of each of the symbols, in the same order.
+const LABELS: [&str; 15] = [
+    "0", "1", "o", "i", ";", "x", ":", "#", "|", "*", "L", "R", "N", "z", "$",
+];
+
+/// How a machine is encoded on the tape of the universal machine.
+///
+/// Symbols and states are numbered, and written as fixed width binary numbers, most significant bit first.
+/// The empty symbol is 0, as is the halting state.
+/// The tape is then laid out as follows:
+///
+/// ```text
+/// $ ;<state><symbol>:<write><move><state> ... # <state>:<symbol> # |<symbol>|<symbol>*<symbol>|<symbol>
+/// ```
+///
+/// First come the rules, then the register with the current state and scanned symbol, and finally the simulated
+/// tape, where the cell under the head starts with `*` instead of `|`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Encoding {
+    /// Symbol of each code
+    pub symbols: Vec<Symbol>,
+    /// State of each code
+    pub states: Vec<State>,
+    pub symbol_width: usize,
+    pub state_width: usize,
+}
+
+impl Encoding {
+    pub fn new(builder: &TransitionFunctionBuilder, initial_state: State, tape: &Tape) -> Self {
+        let mut symbols = vec![Symbol::empty()];
+        let mut states = vec![State::halt()];
+
+        let written = builder.added().into_iter().flat_map(|(input, output)| {
+            let write = match output.write {
+                Write::Print(symbol) => Some(symbol),
+                _ => None,
+            };
+            [Some(input.symbol), write]
+        });
+        for symbol in written.flatten().chain(tape.all_symbols()) {
+            if !symbols.contains(&symbol) {
+                symbols.push(symbol);
+            }
+        }
+
+        let used = builder
+            .added()
+            .into_iter()
+            .flat_map(|(input, output)| [input.state, output.state]);
+        for state in std::iter::once(initial_state).chain(used) {
+            if !states.contains(&state) {
+                states.push(state);
+            }
+        }
+
+        Encoding {
+            symbol_width: width(symbols.len()),
+            state_width: width(states.len()),
+            symbols,
+            states,
+        }
+    }
+
+    /// Input tape of the universal machine, which starts at the `$`.
+    ///
+    /// Fails if the machine deletes cells, which the universal machine can not simulate.
+    pub fn encode(
+        &self,
+        builder: &TransitionFunctionBuilder,
+        initial_state: State,
+        tape: &Tape,
+        head: isize,
+    ) -> Result<Vec<Symbol>, String> {
+        let mut encoded = vec![START];
+        for (input, output) in builder.added() {
+            let write = match output.write {
+                Write::Print(symbol) => symbol,
+                Write::Erase => Symbol::empty(),
+                Write::None => input.symbol,
+                Write::Delete => {
+                    return Err(format!(
+                        "({}, {}) deletes a cell, which can not be encoded",
+                        input.state, input.symbol
+                    ))
+                }
+            };
+
+            encoded.push(RULE);
+            encoded.extend(self.state(input.state));
+            encoded.extend(self.symbol(input.symbol));
+            encoded.push(COLON);
+            encoded.extend(self.symbol(write));
+            encoded.push(match output.action {
+                Action::L => LEFT,
+                Action::R => RIGHT,
+                Action::N => NONE,
+            });
+            encoded.extend(self.state(output.state));
+        }
+
+        encoded.push(HASH);
+        encoded.extend(self.state(initial_state));
+        encoded.push(COLON);
+        encoded.extend(self.symbol(Symbol::empty()));
+        encoded.push(HASH);
+
+        let start = tape.leftmost_written().unwrap_or(head);
+        let end = tape.rightmost_written().unwrap_or(head);
+        for pos in start.min(head)..=end.max(head) {
+            encoded.push(if pos == head { HEAD } else { CELL });
+            encoded.extend(self.symbol(tape.read(pos)));
+        }
+
+        Ok(encoded)
+    }
+
+    /// Simulated tape, starting at position 1, and the head position on it, from the tape of the universal machine.
+    pub fn decode(&self, tape: &Tape) -> Result<(Tape, isize), String> {
+        let symbols = tape.all_symbols();
+        let mut hashes = symbols.iter().enumerate().filter(|(_, s)| **s == HASH);
+        let (start, _) = hashes
+            .nth(1)
+            .ok_or_else(|| String::from("no simulated tape"))?;
+
+        let mut cells = vec![];
+        let mut head = None;
+        let mut rest = &symbols[start + 1..];
+        while let Some(separator) = rest.first().filter(|s| !s.is_empty()) {
+            if *separator == HEAD {
+                head = Some(cells.len() as isize + 1);
+            }
+
+            let bits = rest
+                .get(1..=self.symbol_width)
+                .ok_or_else(|| String::from("incomplete cell"))?;
+            let code = bits.iter().try_fold(0, |code, bit| match *bit {
+                ZERO => Ok(code * 2),
+                ONE => Ok(code * 2 + 1),
+                other => Err(format!("not a bit: {other}")),
+            })?;
+            let symbol = self
+                .symbols
+                .get(code)
+                .ok_or_else(|| format!("unknown symbol code: {code}"))?;
+
+            cells.push(*symbol);
+            rest = &rest[1 + self.symbol_width..];
+        }
+
+        let head = head.ok_or_else(|| String::from("no head"))?;
+        Ok((Tape::from_iter(cells), head))
+    }
+
+    fn symbol(&self, symbol: Symbol) -> Vec<Symbol> {
+        let code = self.symbols.iter().position(|s| *s == symbol).unwrap_or(0);
+        bits(code, self.symbol_width)
+    }
+
+    fn state(&self, state: State) -> Vec<Symbol> {
+        let code = self.states.iter().position(|s| *s == state).unwrap_or(0);
+        bits(code, self.state_width)
+    }
+}
+
+/// Number of bits needed to write codes up to, but excluding, the given number, and at least 1.
+fn width(codes: usize) -> usize {
+    let mut width = 1;
+    while 1 << width < codes {
+        width += 1;
+    }
+    width
+}
+
+fn bits(code: usize, width: usize) -> Vec<Symbol> {
+    (0..width)
+        .rev()
+        .map(|i| if code >> i & 1 == 1 { ONE } else { ZERO })
+        .collect()
+}
+
+/// Universal machine simulating an encoded 2-state busy beaver.
+pub fn universal_turing_machine() -> UniverseMetadata {
+    let beaver = two_state_busy_beaver();
+    let builder = beaver.transition_function_buidler;
+    let (tape, head) = (beaver.universe.tape, beaver.universe.pos);
+    let state = beaver.universe.machine.state;
+
+    let encoding = Encoding::new(&builder, state, &tape);
+    let encoded = encoding.encode(&builder, state, &tape, head);
+    universal_turing_machine_for(encoded.expect("busy beaver does not delete cells"))
+}
+
+/// Universal machine with the given encoded machine and input on its tape.
+pub fn universal_turing_machine_for(encoded: Vec<Symbol>) -> UniverseMetadata {
+    let name = String::from("universal Turing machine");
+    let initial_head = 1_usize;
+
+    let mut utm = Builder {
+        symbol_set: SYMBOLS.to_vec(),
+        ..Default::default()
+    };
+    utm.add_transitions();
+    let halt_check = utm.state("halt_check");
+
+    let transition_function = utm.builder.build();
+    let universe = Universe::new(encoded, initial_head, halt_check, transition_function);
+
+    UniverseMetadata {
+        name,
+        head_offset_hint: initial_head,
+        symbol_set: SYMBOLS.to_vec(),
+        state_set: utm.state_set,
+        display_state_as: utm.display_state_as,
+        display_symbol_as: SYMBOLS.into_iter().zip(LABELS.map(String::from)).collect(),
+        transition_function_buidler: utm.builder,
+        universe,
+    }
+}
+
+impl Builder {
+    /// Unmark bits, until the given symbol.
+    fn unmark(&mut self, from: &str, action: Action, until: Symbol, stop_action: Action, to: &str) {
+        self.add(from, MARKED_ZERO, Write::from(ZERO), action, from);
+        self.add(from, MARKED_ONE, Write::from(ONE), action, from);
+        self.add(from, until, Write::None, stop_action, to);
+    }
+
+    fn add_transitions(&mut self) {
+        let marked = |bit: usize| Write::from(if bit == 0 { MARKED_ZERO } else { MARKED_ONE });
+        let skip = Write::None;
+
+        // halt once the state in the register is zero
+        self.seek("halt_check", L, &[(START, skip, R, "halt_to_register")]);
+        self.seek("halt_to_register", R, &[(HASH, skip, R, "halt_register")]);
+        self.add("halt_register", ZERO, skip, R, "halt_register");
+        self.add("halt_register", ONE, skip, N, "read");
+        self.add("halt_register", COLON, skip, N, "!");
+
+        // copy the symbol under the simulated head to the register
+        self.seek("read", R, &[(HEAD, skip, R, "read_bit")]);
+        self.add("read_bit", MARKED_ZERO, skip, R, "read_bit");
+        self.add("read_bit", MARKED_ONE, skip, R, "read_bit");
+        self.add("read_bit", CELL, skip, L, "read_unmark_cell");
+        self.add("read_bit", Symbol::empty(), skip, L, "read_unmark_cell");
+        for bit in [0, 1] {
+            let (carry, store) = (format!("read_carry_{bit}"), format!("read_store_{bit}"));
+            self.add("read_bit", [ZERO, ONE][bit], marked(bit), L, &carry);
+            self.seek(&carry, L, &[(COLON, skip, R, &store)]);
+            self.add(&store, MARKED_ZERO, skip, R, &store);
+            self.add(&store, MARKED_ONE, skip, R, &store);
+            self.add(&store, ZERO, marked(bit), R, "read");
+            self.add(&store, ONE, marked(bit), R, "read");
+        }
+        self.unmark("read_unmark_cell", L, HEAD, L, "read_to_register");
+        self.seek(
+            "read_to_register",
+            L,
+            &[(COLON, skip, R, "read_unmark_register")],
+        );
+        self.unmark("read_unmark_register", R, HASH, L, "match");
+
+        // compare the register to the first rule which was not rejected, bit by bit
+        self.seek("match", L, &[(START, skip, R, "match_to_register")]);
+        self.seek("match_to_register", R, &[(HASH, skip, R, "match_bit")]);
+        self.add("match_bit", MARKED_ZERO, skip, R, "match_bit");
+        self.add("match_bit", MARKED_ONE, skip, R, "match_bit");
+        self.add("match_bit", COLON, skip, R, "match_bit");
+        self.add("match_bit", HASH, skip, L, "match_unmark");
+        for bit in [0, 1] {
+            let (carry, rule, compare) = (
+                format!("match_carry_{bit}"),
+                format!("match_rule_{bit}"),
+                format!("match_compare_{bit}"),
+            );
+            self.add("match_bit", [ZERO, ONE][bit], marked(bit), L, &carry);
+            self.seek(&carry, L, &[(START, skip, R, &rule)]);
+            self.seek(&rule, R, &[(RULE, skip, R, &compare)]);
+            self.add(&compare, MARKED_ZERO, skip, R, &compare);
+            self.add(&compare, MARKED_ONE, skip, R, &compare);
+            self.add(&compare, [ZERO, ONE][bit], marked(bit), N, "match");
+            self.add(&compare, [ZERO, ONE][1 - bit], skip, L, "reject");
+        }
+        self.seek(
+            "reject",
+            L,
+            &[(RULE, Write::from(REJECTED), N, "reject_unmark")],
+        );
+        self.seek(
+            "reject_unmark",
+            L,
+            &[(START, skip, R, "reject_unmark_rules")],
+        );
+        self.unmark_region("reject_unmark_rules", "reject_unmark_register", "match");
+
+        self.add(
+            "match_unmark",
+            MARKED_ZERO,
+            Write::from(ZERO),
+            L,
+            "match_unmark",
+        );
+        self.add(
+            "match_unmark",
+            MARKED_ONE,
+            Write::from(ONE),
+            L,
+            "match_unmark",
+        );
+        self.add("match_unmark", COLON, skip, L, "match_unmark");
+        self.add("match_unmark", HASH, skip, N, "apply");
+
+        // copy what the matching rule writes to the cell under the simulated head
+        self.seek("apply", L, &[(START, skip, R, "apply_rule")]);
+        self.seek("apply_rule", R, &[(RULE, skip, R, "apply_output")]);
+        self.seek("apply_output", R, &[(COLON, skip, R, "write_bit")]);
+        self.add("write_bit", MARKED_ZERO, skip, R, "write_bit");
+        self.add("write_bit", MARKED_ONE, skip, R, "write_bit");
+        for bit in [0, 1] {
+            let (carry, store) = (format!("write_carry_{bit}"), format!("write_store_{bit}"));
+            self.add("write_bit", [ZERO, ONE][bit], marked(bit), R, &carry);
+            self.seek(&carry, R, &[(HEAD, skip, R, &store)]);
+            self.add(&store, MARKED_ZERO, skip, R, &store);
+            self.add(&store, MARKED_ONE, skip, R, &store);
+            self.add(&store, ZERO, marked(bit), N, "apply");
+            self.add(&store, ONE, marked(bit), N, "apply");
+        }
+
+        // then copy its next state to the register
+        for action in [LEFT, RIGHT, NONE] {
+            self.add("write_bit", action, skip, R, "state_bit");
+            self.add("state_output", action, skip, R, "state_bit");
+        }
+        self.seek("state", L, &[(START, skip, R, "state_rule")]);
+        self.seek("state_rule", R, &[(RULE, skip, R, "state_output")]);
+        for symbol in [ZERO, ONE, MARKED_ZERO, MARKED_ONE, COLON] {
+            self.add("state_output", symbol, skip, R, "state_output");
+        }
+        self.add("state_bit", MARKED_ZERO, skip, R, "state_bit");
+        self.add("state_bit", MARKED_ONE, skip, R, "state_bit");
+        for end in [RULE, REJECTED, HASH] {
+            self.add("state_bit", end, skip, L, "move");
+        }
+        for bit in [0, 1] {
+            let (carry, store) = (format!("state_carry_{bit}"), format!("state_store_{bit}"));
+            self.add("state_bit", [ZERO, ONE][bit], marked(bit), R, &carry);
+            self.seek(&carry, R, &[(HASH, skip, R, &store)]);
+            self.add(&store, MARKED_ZERO, skip, R, &store);
+            self.add(&store, MARKED_ONE, skip, R, &store);
+            self.add(&store, ZERO, marked(bit), N, "state");
+            self.add(&store, ONE, marked(bit), N, "state");
+        }
+
+        // remember how to move, and unmark everything before moving
+        self.seek(
+            "move",
+            L,
+            &[
+                (LEFT, skip, L, "clean_left"),
+                (RIGHT, skip, L, "clean_right"),
+                (NONE, skip, L, "clean_none"),
+            ],
+        );
+        for name in ["left", "right", "none"] {
+            let (clean, sweep) = (format!("clean_{name}"), format!("sweep_{name}"));
+            self.seek(&clean, L, &[(START, skip, R, &sweep)]);
+            self.seek(
+                &sweep,
+                R,
+                &[
+                    (MARKED_ZERO, Write::from(ZERO), R, &sweep),
+                    (MARKED_ONE, Write::from(ONE), R, &sweep),
+                    (REJECTED, Write::from(RULE), R, &sweep),
+                    (Symbol::empty(), skip, L, &format!("move_{name}")),
+                ],
+            );
+        }
+
+        self.add_moves();
+    }
+
+    /// Unmark the rules and register, starting at the first rule.
+    fn unmark_region(&mut self, rules: &str, register: &str, to: &str) {
+        self.seek(
+            rules,
+            R,
+            &[
+                (MARKED_ZERO, Write::from(ZERO), R, rules),
+                (MARKED_ONE, Write::from(ONE), R, rules),
+                (HASH, Write::None, R, register),
+            ],
+        );
+        self.seek(
+            register,
+            R,
+            &[
+                (MARKED_ZERO, Write::from(ZERO), R, register),
+                (MARKED_ONE, Write::from(ONE), R, register),
+                (HASH, Write::None, L, to),
+            ],
+        );
+    }
+
+    fn add_moves(&mut self) {
+        let (skip, halt_check) = (Write::None, "halt_check");
+
+        self.add("move_none", ZERO, skip, N, halt_check);
+        self.add("move_none", ONE, skip, N, halt_check);
+
+        // move right, appending a cell when there is none
+        self.seek(
+            "move_right",
+            L,
+            &[(HEAD, Write::from(CELL), R, "move_right_cell")],
+        );
+        self.add("move_right_cell", ZERO, skip, R, "move_right_cell");
+        self.add("move_right_cell", ONE, skip, R, "move_right_cell");
+        self.add("move_right_cell", CELL, Write::from(HEAD), N, halt_check);
+        self.add(
+            "move_right_cell",
+            Symbol::empty(),
+            Write::from(HEAD),
+            N,
+            "grow",
+        );
+
+        // a new cell is as wide as the symbol in the register
+        self.seek("grow", L, &[(COLON, skip, R, "grow_bit")]);
+        self.add("grow_bit", MARKED_ZERO, skip, R, "grow_bit");
+        self.add("grow_bit", MARKED_ONE, skip, R, "grow_bit");
+        self.add("grow_bit", ZERO, Write::from(MARKED_ZERO), R, "grow_append");
+        self.add("grow_bit", ONE, Write::from(MARKED_ONE), R, "grow_append");
+        self.add("grow_bit", HASH, skip, L, "grow_unmark");
+        self.seek(
+            "grow_append",
+            R,
+            &[(Symbol::empty(), Write::from(ZERO), N, "grow")],
+        );
+        self.unmark("grow_unmark", L, COLON, N, halt_check);
+
+        // move left, inserting a cell when there is none
+        self.seek("move_left", L, &[(HEAD, skip, L, "move_left_peek")]);
+        self.add("move_left_peek", ZERO, skip, R, "move_left_cell");
+        self.add("move_left_peek", ONE, skip, R, "move_left_cell");
+        self.add("move_left_peek", HASH, skip, R, "insert");
+        self.add(
+            "move_left_cell",
+            HEAD,
+            Write::from(CELL),
+            L,
+            "move_left_previous",
+        );
+        self.add("move_left_previous", ZERO, skip, L, "move_left_previous");
+        self.add("move_left_previous", ONE, skip, L, "move_left_previous");
+        self.add("move_left_previous", CELL, Write::from(HEAD), N, halt_check);
+
+        // insert by shifting the simulated tape one symbol at a time, once for the head and once per bit
+        self.add("insert", HEAD, Write::from(CELL), L, "insert_start");
+        self.add("insert_start", HASH, skip, R, "shift_head");
+        for (shift, write) in [("shift_head", HEAD), ("shift_zero", NEW_ZERO)] {
+            for symbol in SYMBOLS {
+                self.add(
+                    shift,
+                    symbol,
+                    Write::from(write),
+                    R,
+                    &format!("carry_{symbol}"),
+                );
+            }
+        }
+        for carry in SYMBOLS {
+            let carry_state = format!("carry_{carry}");
+            for symbol in SYMBOLS {
+                self.add(
+                    &carry_state,
+                    symbol,
+                    Write::from(carry),
+                    R,
+                    &format!("carry_{symbol}"),
+                );
+            }
+            self.add(
+                &carry_state,
+                Symbol::empty(),
+                Write::from(carry),
+                L,
+                "insert_next",
+            );
+        }
+        self.seek("insert_next", L, &[(COLON, skip, R, "insert_bit")]);
+        self.add("insert_bit", MARKED_ZERO, skip, R, "insert_bit");
+        self.add("insert_bit", MARKED_ONE, skip, R, "insert_bit");
+        self.add(
+            "insert_bit",
+            ZERO,
+            Write::from(MARKED_ZERO),
+            R,
+            "insert_to_tape",
+        );
+        self.add(
+            "insert_bit",
+            ONE,
+            Write::from(MARKED_ONE),
+            R,
+            "insert_to_tape",
+        );
+        self.add("insert_bit", HASH, skip, L, "insert_unmark");
+        self.seek("insert_to_tape", R, &[(HASH, skip, R, "insert_skip")]);
+        self.add("insert_skip", HEAD, skip, R, "insert_skip");
+        self.add("insert_skip", NEW_ZERO, skip, R, "insert_skip");
+        for symbol in [ZERO, ONE, CELL] {
+            self.add("insert_skip", symbol, skip, N, "shift_zero");
+        }
+        self.unmark("insert_unmark", L, COLON, R, "insert_finish");
+        self.seek("insert_finish", R, &[(HASH, skip, R, "insert_zeroes")]);
+        self.add("insert_zeroes", HEAD, skip, R, "insert_zeroes");
+        self.add(
+            "insert_zeroes",
+            NEW_ZERO,
+            Write::from(ZERO),
+            R,
+            "insert_zeroes",
+        );
+        self.add("insert_zeroes", CELL, skip, N, halt_check);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::presets::arithmetic::binary_addition;
+    use crate::presets::busy_beaver::{three_state_busy_beaver, two_state_busy_beaver};
+    use crate::presets::UniverseMetadata;
+    use crate::universe::Symbol;
+
+    use super::{bits, universal_turing_machine, universal_turing_machine_for, width, Encoding};
+
+    const MAX_TICKS: usize = 10_000_000;
+
+    /// Run the machine directly and through the universal machine, and compare their tapes.
+    fn simulate(universe_meta: UniverseMetadata) {
+        let builder = &universe_meta.transition_function_buidler;
+        let mut universe = universe_meta.universe.clone();
+        let state = universe.machine.state;
+
+        let encoding = Encoding::new(builder, state, &universe.tape);
+        let encoded = encoding
+            .encode(builder, state, &universe.tape, universe.pos)
+            .unwrap();
+        let mut utm = universal_turing_machine_for(encoded).universe;
+
+        while !universe.machine.state.is_halted() {
+            universe.tick().unwrap();
+        }
+        while !utm.machine.state.is_halted() {
+            assert!(utm.ticks < MAX_TICKS, "universal machine did not halt");
+            utm.tick().unwrap();
+        }
+
+        let (tape, _) = encoding.decode(&utm.tape).unwrap();
+        let trim = |tape: String| tape.trim_matches('_').to_owned();
+        assert_eq!(trim(tape.to_string()), trim(universe.tape.to_string()));
+    }
+
+    #[test_case(1, 1)]
+    #[test_case(2, 1)]
+    #[test_case(3, 2)]
+    #[test_case(5, 3)]
+    fn width_fits_codes(codes: usize, expected: usize) {
+        assert_eq!(width(codes), expected);
+    }
+
+    #[test]
+    fn bits_most_significant_first() {
+        let (zero, one) = (Symbol::from(0), Symbol::from(1));
+
+        assert_eq!(bits(6, 4), vec![zero, one, one, zero]);
+    }
+
+    #[test]
+    fn encode_decode_tape() {
+        let beaver = two_state_busy_beaver().with_input("1_1").unwrap();
+        let (builder, universe) = (&beaver.transition_function_buidler, &beaver.universe);
+        let encoding = Encoding::new(builder, universe.machine.state, &universe.tape);
+
+        let encoded = encoding
+            .encode(builder, universe.machine.state, &universe.tape, 2)
+            .unwrap();
+        let (tape, head) = encoding.decode(&encoded.into_iter().collect()).unwrap();
+
+        assert_eq!(tape, universe.tape);
+        assert_eq!(tape.read(head), universe.tape.read(2));
+    }
+
+    #[test]
+    fn utm_reproduces_beaver_2() {
+        let mut utm = universal_turing_machine();
+        while !utm.universe.machine.state.is_halted() {
+            assert!(
+                utm.universe.ticks < MAX_TICKS,
+                "universal machine did not halt"
+            );
+            utm.universe.tick().unwrap();
+        }
+
+        let mut beaver = two_state_busy_beaver();
+        while !beaver.universe.machine.state.is_halted() {
+            beaver.universe.tick().unwrap();
+        }
+
+        let beaver_2 = two_state_busy_beaver();
+        let encoding = Encoding::new(
+            &beaver_2.transition_function_buidler,
+            beaver_2.universe.machine.state,
+            &beaver_2.universe.tape,
+        );
+        let (tape, _) = encoding.decode(&utm.universe.tape).unwrap();
+
+        assert_eq!(tape, beaver.universe.tape);
+        assert_eq!(tape.to_string(), String::from("1111"));
+    }
+
+    #[test]
+    fn utm_simulates_beaver_3() {
+        simulate(three_state_busy_beaver());
+    }
+
+    #[test]
+    fn utm_simulates_binary_addition() {
+        simulate(binary_addition().with_input("1121").unwrap());
+    }
+}
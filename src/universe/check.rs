@@ -0,0 +1,300 @@
+use std::collections::{HashMap, HashSet};
+
+use super::function::{Input, TransitionFunctionBuilder};
+use super::machine::{State, Write};
+use super::Symbol;
+
+/// Problem found in a transition function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Issue {
+    /// Input which was added more than once, where the last output wins
+    DuplicateInput(Input),
+    /// Declared state and symbol without a transition, where the machine gets stuck
+    MissingInput(Input),
+    /// State which can not be reached from the initial state
+    Unreachable(State),
+    /// Reachable state from which the machine can never halt
+    NoPathToHalt(State),
+    /// Symbol which is written, but not declared
+    UndeclaredSymbol(Symbol),
+    /// State which has a transition, is moved to or is the initial state, but is not declared
+    UndeclaredState(State),
+}
+
+impl Issue {
+    /// Errors make the transition function ambiguous, while the other issues may be intended.
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            Issue::DuplicateInput(_) | Issue::UndeclaredSymbol(_) | Issue::UndeclaredState(_)
+        )
+    }
+}
+
+impl TransitionFunctionBuilder {
+    /// Issues with the transitions, in the order they were added, over the declared symbols and states.
+    ///
+    /// The empty symbol is always declared, while undeclared states are checked like the declared ones.
+    pub fn check(
+        &self,
+        symbol_set: &[Symbol],
+        state_set: &[State],
+        initial_state: State,
+    ) -> Vec<Issue> {
+        let added = self.added();
+        let mut issues = vec![];
+
+        let mut seen = HashSet::new();
+        for (input, _) in &added {
+            if !seen.insert(*input) {
+                issues.push(Issue::DuplicateInput(*input));
+            }
+        }
+
+        let mut undeclared = vec![];
+        for (_, output) in &added {
+            if let Write::Print(symbol) = output.write {
+                if !symbol.is_empty()
+                    && !symbol_set.contains(&symbol)
+                    && !undeclared.contains(&symbol)
+                {
+                    undeclared.push(symbol);
+                }
+            }
+        }
+        issues.extend(undeclared.into_iter().map(Issue::UndeclaredSymbol));
+
+        let mut states = state_set.to_vec();
+        let mut known: HashSet<State> = states.iter().cloned().collect();
+        let used = added
+            .iter()
+            .flat_map(|(input, output)| [input.state, output.state]);
+        for state in used.chain([initial_state]) {
+            if !state.is_halted() && known.insert(state) {
+                issues.push(Issue::UndeclaredState(state));
+                states.push(state);
+            }
+        }
+
+        let mut symbols = vec![Symbol::empty()];
+        symbols.extend(symbol_set.iter().filter(|s| !s.is_empty()));
+        for state in &states {
+            for symbol in &symbols {
+                let input = Input {
+                    state: *state,
+                    symbol: *symbol,
+                };
+                if !seen.contains(&input) {
+                    issues.push(Issue::MissingInput(input));
+                }
+            }
+        }
+
+        let mut next: HashMap<State, Vec<State>> = HashMap::new();
+        let mut previous: HashMap<State, Vec<State>> = HashMap::new();
+        for (input, output) in &added {
+            next.entry(input.state).or_default().push(output.state);
+            previous.entry(output.state).or_default().push(input.state);
+        }
+
+        let reachable = connected(initial_state, &next);
+        // states from which the halting state can be reached
+        let halting = connected(State::halt(), &previous);
+
+        for state in states {
+            if !reachable.contains(&state) {
+                issues.push(Issue::Unreachable(state));
+            } else if !halting.contains(&state) {
+                issues.push(Issue::NoPathToHalt(state));
+            }
+        }
+
+        issues
+    }
+}
+
+/// States which can be reached from the given state by following the edges, including the state itself.
+fn connected(from: State, edges: &HashMap<State, Vec<State>>) -> HashSet<State> {
+    let mut connected = HashSet::from([from]);
+    let mut pending = vec![from];
+    while let Some(state) = pending.pop() {
+        for to in edges.get(&state).into_iter().flatten() {
+            if connected.insert(*to) {
+                pending.push(*to);
+            }
+        }
+    }
+    connected
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::compiler::binary::{reduce, BlockEncoding};
+    use crate::compiler::{brainfuck, language};
+    use crate::presets::registry::{build, presets};
+    use crate::presets::UniverseMetadata;
+    use crate::universe::function::{Input, TransitionFunctionBuilder};
+    use crate::universe::machine::{Action, State, Write};
+    use crate::universe::Symbol;
+
+    use super::Issue;
+
+    #[test]
+    fn complete_machine_has_no_issues() {
+        let (a, b) = (State::from(0), State::from(1));
+        let (se, s1) = (Symbol::empty(), Symbol::from(1));
+        let mut builder = TransitionFunctionBuilder::default();
+        builder.add(a, se, Write::from(s1), Action::R, b);
+        builder.add(a, s1, Write::from(s1), Action::L, b);
+        builder.add(b, se, Write::from(s1), Action::L, a);
+        builder.add(b, s1, Write::from(s1), Action::R, State::halt());
+
+        assert_eq!(builder.check(&[se, s1], &[a, b], a), vec![]);
+    }
+
+    #[test]
+    fn issues() {
+        let (a, b, c, d) = (
+            State::from(0),
+            State::from(1),
+            State::from(2),
+            State::from(3),
+        );
+        let (se, s1, s2) = (Symbol::empty(), Symbol::from(1), Symbol::from(2));
+        let mut builder = TransitionFunctionBuilder::default();
+        builder.add(a, se, Write::from(s2), Action::R, b);
+        builder.add(a, se, Write::from(s1), Action::R, State::halt());
+        builder.add(a, s1, Write::None, Action::R, State::halt());
+        builder.add(b, se, Write::None, Action::R, b);
+        builder.add(b, s1, Write::None, Action::R, b);
+        builder.add(c, se, Write::None, Action::R, State::halt());
+        builder.add(c, s1, Write::None, Action::R, State::halt());
+
+        let input = |state, symbol| Input { state, symbol };
+        assert_eq!(
+            builder.check(&[s1], &[a, b, c, d], a),
+            vec![
+                Issue::DuplicateInput(input(a, se)),
+                Issue::UndeclaredSymbol(s2),
+                Issue::MissingInput(input(d, se)),
+                Issue::MissingInput(input(d, s1)),
+                Issue::NoPathToHalt(b),
+                Issue::Unreachable(c),
+                Issue::Unreachable(d),
+            ]
+        );
+    }
+
+    #[test]
+    fn undeclared_states() {
+        let (a, b) = (State::from(0), State::from(1));
+        let (se, s1) = (Symbol::empty(), Symbol::from(1));
+        let mut builder = TransitionFunctionBuilder::default();
+        builder.add(a, se, Write::from(s1), Action::R, b);
+        builder.add(a, s1, Write::from(s1), Action::R, State::halt());
+        builder.add(b, se, Write::from(s1), Action::L, a);
+        builder.add(b, s1, Write::from(s1), Action::L, a);
+
+        assert_eq!(
+            builder.check(&[se, s1], &[a], a),
+            vec![Issue::UndeclaredState(b)]
+        );
+    }
+
+    /// Errors and warnings found in the machine.
+    fn errors_and_warnings(universe_meta: &UniverseMetadata) -> (Vec<Issue>, Vec<Issue>) {
+        let issues = universe_meta.transition_function_buidler.check(
+            &universe_meta.symbol_set,
+            &universe_meta.state_set,
+            universe_meta.universe.machine.state,
+        );
+        issues.into_iter().partition(Issue::is_error)
+    }
+
+    /// Registered presets, members of the preset families, compiled programs, and their reductions to 2 symbols.
+    fn machines() -> Vec<UniverseMetadata> {
+        let mut machines: Vec<_> = presets().iter().map(|preset| (preset.build)()).collect();
+        for name in [
+            "counter_3",
+            "counter_10",
+            "counter_36",
+            "decrementer_2",
+            "decrementer_16",
+            "counter_10_to_12",
+            "counter_16_to_255",
+        ] {
+            machines.push(build(name).unwrap());
+        }
+
+        let increment = "while 0 1
+    right
+end
+left
+carry:
+if 1
+    write 0
+    left
+    goto carry
+end
+write 1";
+        machines.push(
+            language::compile(increment)
+                .unwrap()
+                .into_universe_meta("increment"),
+        );
+        for program in [
+            "+>.",
+            ",[.[-],]",
+            ">,[>,]<[.<]",
+            "+++[>++++[>+++++<-]<-]>>.",
+        ] {
+            machines.push(
+                brainfuck::compile(program)
+                    .unwrap()
+                    .into_universe_meta(program),
+            );
+        }
+
+        let reduced: Vec<_> = machines
+            .iter()
+            .filter_map(|universe_meta| {
+                reduce(universe_meta, &BlockEncoding::new(universe_meta)).ok()
+            })
+            .collect();
+        machines.extend(reduced);
+        machines
+    }
+
+    #[test]
+    fn machines_have_no_errors() {
+        for universe_meta in machines() {
+            let (errors, _) = errors_and_warnings(&universe_meta);
+            assert_eq!(errors, vec![], "{}", universe_meta.name);
+        }
+    }
+
+    #[test_case("beaver_1")]
+    #[test_case("beaver_3")]
+    #[test_case("beaver_5")]
+    #[test_case("decrementer_5")]
+    #[test_case("palindrome")]
+    #[test_case("anbn")]
+    #[test_case("anbncn")]
+    #[test_case("parentheses")]
+    #[test_case("copy")]
+    fn complete_machines_have_no_warnings(name: &str) {
+        let universe_meta = build(name).unwrap();
+        assert_eq!(errors_and_warnings(&universe_meta), (vec![], vec![]));
+    }
+
+    #[test_case("beaver_2")]
+    #[test_case("beaver_4")]
+    #[test_case("parentheses")]
+    fn complete_reductions_have_no_warnings(name: &str) {
+        let universe_meta = build(name).unwrap();
+        let reduced = reduce(&universe_meta, &BlockEncoding::new(&universe_meta)).unwrap();
+        assert_eq!(errors_and_warnings(&reduced), (vec![], vec![]));
+    }
+}
@@ -0,0 +1,255 @@
+use std::ops::Range;
+
+use super::tape::Tape;
+use super::{Symbol, Universe};
+
+/// How a run ended, after at most some number of ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Halting {
+    Halted,
+    /// The machine failed, such as for a missing transition or moving past the end of a bounded tape
+    Failed,
+    /// The machine was still running once it ran out of ticks
+    Running,
+}
+
+/// Last configuration of a universe run on some input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub halting: Halting,
+    pub ticks: usize,
+    pub head: isize,
+    pub tape: Tape,
+    /// Why the machine failed, if it did
+    pub error: Option<String>,
+}
+
+/// Input on which two machines behave differently, along with how each of them ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+    pub input: Vec<Symbol>,
+    pub left: Outcome,
+    pub right: Outcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Report {
+    /// Number of inputs both machines were run on
+    pub compared: usize,
+    /// Number of inputs on which both machines ran out of ticks, so they can not be told apart
+    pub undecided: usize,
+    /// First input on which the machines differ, if any
+    pub difference: Option<Difference>,
+}
+
+impl Outcome {
+    /// Run the universe with the input on its tape, the first symbol of which is at position 1.
+    ///
+    /// Only the tape is replaced, so the universe keeps its own initial state, head position and tape mode.
+    pub fn of(universe: &Universe, input: &[Symbol], max_ticks: usize) -> Self {
+        let mut universe = universe.clone();
+        universe.tape = Tape::from_iter(input.iter().copied());
+
+        let mut error = None;
+        while !universe.machine.state.is_halted() && universe.ticks < max_ticks {
+            if let Err(err) = universe.tick() {
+                error = Some(err);
+                break;
+            }
+        }
+
+        let halting = match (&error, universe.machine.state.is_halted()) {
+            (Some(_), _) => Halting::Failed,
+            (None, true) => Halting::Halted,
+            (None, false) => Halting::Running,
+        };
+        Outcome {
+            halting,
+            ticks: universe.ticks,
+            head: universe.pos,
+            tape: universe.tape,
+            error,
+        }
+    }
+
+    /// Cells from the leftmost to the rightmost non-empty one, which is empty for an empty tape.
+    pub fn written(&self) -> Range<isize> {
+        match self.tape.non_empty_bounds() {
+            Some((leftmost, rightmost)) => leftmost..rightmost + 1,
+            None => 0..0,
+        }
+    }
+
+    /// Whether both stopped the same way with the same tape and head, or both are still running.
+    ///
+    /// Heads and tapes are compared at their absolute positions, as both inputs start at position 1.
+    ///
+    /// Runs which are still running are not compared any further, as one machine may just be slower than the other.
+    pub fn agrees_with(&self, other: &Outcome) -> bool {
+        if self.halting != other.halting {
+            return false;
+        }
+        self.halting == Halting::Running
+            || self.head == other.head
+                && self.written() == other.written()
+                && self.tape.symbols(self.written()) == other.tape.symbols(other.written())
+    }
+}
+
+/// Run both universes on each input, until the first input on which they differ.
+///
+/// The universes are the configurations to start from, whose tapes are replaced by each input.
+/// Each keeps its own initial state, head position and tape mode, which may differ between the two.
+pub fn compare(
+    left: &Universe,
+    right: &Universe,
+    inputs: impl IntoIterator<Item = Vec<Symbol>>,
+    max_ticks: usize,
+) -> Report {
+    let mut report = Report::default();
+    for input in inputs {
+        let left = Outcome::of(left, &input, max_ticks);
+        let right = Outcome::of(right, &input, max_ticks);
+        report.compared += 1;
+
+        if !left.agrees_with(&right) {
+            report.difference = Some(Difference { input, left, right });
+            break;
+        }
+        if left.halting == Halting::Running {
+            report.undecided += 1;
+        }
+    }
+    report
+}
+
+/// Every input of at most the given length over the symbols, shortest first, starting with the empty input.
+pub fn inputs_up_to(symbols: &[Symbol], length: usize) -> Vec<Vec<Symbol>> {
+    let mut inputs = vec![vec![]];
+    let mut previous = vec![vec![]];
+    for _ in 0..length {
+        previous = previous
+            .iter()
+            .flat_map(|input: &Vec<Symbol>| {
+                symbols.iter().map(move |symbol| {
+                    let mut input = input.clone();
+                    input.push(*symbol);
+                    input
+                })
+            })
+            .collect();
+        inputs.extend(previous.iter().cloned());
+    }
+    inputs
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use crate::presets::arithmetic::binary_addition;
+    use crate::presets::busy_beaver::{three_state_busy_beaver, two_state_busy_beaver};
+    use crate::presets::recognizer::palindrome;
+    use crate::universe::function::Output;
+    use crate::universe::machine::{Action, State, Write};
+    use crate::universe::Symbol;
+
+    use super::{compare, inputs_up_to, Halting, Outcome};
+
+    #[test_case(0, 1)]
+    #[test_case(1, 3)]
+    #[test_case(3, 15)]
+    fn inputs_up_to_length(length: usize, expected: usize) {
+        let symbols = [Symbol::from(0), Symbol::from(1)];
+
+        assert_eq!(inputs_up_to(&symbols, length).len(), expected);
+    }
+
+    #[test]
+    fn inputs_are_shortest_first() {
+        let (a, b) = (Symbol::from(0), Symbol::from(1));
+
+        assert_eq!(
+            inputs_up_to(&[a, b], 2),
+            vec![
+                vec![],
+                vec![a],
+                vec![b],
+                vec![a, a],
+                vec![a, b],
+                vec![b, a],
+                vec![b, b]
+            ]
+        );
+    }
+
+    #[test]
+    fn machine_is_equivalent_to_itself() {
+        let palindrome = palindrome().universe_meta;
+        let symbols: Vec<_> = palindrome.symbol_set.clone();
+        let inputs = inputs_up_to(&symbols[1..], 4);
+        let count = inputs.len();
+
+        let report = compare(&palindrome.universe, &palindrome.universe, inputs, 1000);
+        assert_eq!(report.compared, count);
+        assert_eq!(report.undecided, 0);
+        assert_eq!(report.difference, None);
+    }
+
+    #[test]
+    fn different_tapes_differ() {
+        let beaver = two_state_busy_beaver();
+        let mut other = beaver.clone();
+        // erase rather than print 1 when halting
+        other
+            .transition_function_buidler
+            .set_output(
+                3,
+                Output {
+                    write: Write::Erase,
+                    action: Action::R,
+                    state: State::halt(),
+                },
+            )
+            .unwrap();
+        let function = other.transition_function_buidler.build();
+        other.universe.machine.set_transition_function(function);
+
+        let report = compare(&beaver.universe, &other.universe, [vec![]], 100);
+        let difference = report.difference.unwrap();
+        assert_eq!(difference.input, vec![]);
+        assert_eq!(difference.left.halting, Halting::Halted);
+        assert_eq!(difference.right.halting, Halting::Halted);
+        assert_eq!(difference.left.head, difference.right.head);
+        assert_eq!(difference.left.tape.non_empty(), 4);
+        assert_eq!(difference.right.tape.non_empty(), 3);
+    }
+
+    #[test]
+    fn halting_differs_from_running_out_of_ticks() {
+        let two = two_state_busy_beaver().universe;
+        let three = three_state_busy_beaver().universe;
+
+        let report = compare(&two, &three, [vec![]], 8);
+        let difference = report.difference.unwrap();
+        assert_eq!(difference.left.halting, Halting::Halted);
+        assert_eq!(difference.right.halting, Halting::Running);
+    }
+
+    #[test]
+    fn running_out_of_ticks_is_undecided() {
+        let adder = binary_addition().universe;
+
+        let report = compare(&adder, &adder, [vec![Symbol::from(1)]], 0);
+        assert_eq!(report.undecided, 1);
+        assert_eq!(report.difference, None);
+    }
+
+    #[test]
+    fn written_ignores_empty_cells() {
+        let beaver = two_state_busy_beaver().universe;
+        let outcome = Outcome::of(&beaver, &[Symbol::empty(), Symbol::from(1)], 0);
+
+        assert_eq!(outcome.written(), 2..3);
+    }
+}
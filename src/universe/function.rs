@@ -3,13 +3,15 @@ use std::collections::HashMap;
 use super::machine::{Action, State, Write};
 use super::Symbol;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Input {
     pub state: State,
     pub symbol: Symbol,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Output {
     pub write: Write,
     pub action: Action,
@@ -17,9 +19,15 @@ pub struct Output {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
-pub struct TransitionFunction(HashMap<Input, Output>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct TransitionFunction(
+    #[cfg_attr(feature = "serde", serde(with = "super::pairs"))] HashMap<Input, Output>,
+);
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct TransitionFunctionBuilder(Vec<(Input, Output)>);
 
 impl TransitionFunction {
@@ -56,11 +64,21 @@ impl TransitionFunctionBuilder {
         ));
     }
 
+    /// Replace the output of the transition added at the given index.
+    pub fn set_output(&mut self, index: usize, output: Output) -> Result<(), String> {
+        let (_, old) = self
+            .0
+            .get_mut(index)
+            .ok_or_else(|| format!("no transition at {index}"))?;
+        *old = output;
+        Ok(())
+    }
+
     pub fn added(&self) -> Vec<(Input, Output)> {
         self.0.clone()
     }
 
     pub fn build(&self) -> TransitionFunction {
-        TransitionFunction(HashMap::from_iter(self.0.clone().into_iter()))
+        TransitionFunction(HashMap::from_iter(self.0.clone()))
     }
 }
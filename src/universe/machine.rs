@@ -3,10 +3,13 @@ use std::fmt::Display;
 use super::function::TransitionFunction;
 use super::Symbol;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct State(Option<usize>);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Action {
     L,
     R,
@@ -14,13 +17,18 @@ pub enum Action {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Write {
     Print(Symbol),
+    /// Leave the cell empty
     Erase,
+    /// Remove the cell, moving the cells to its right one position to the left
+    Delete,
     None,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Machine {
     pub state: State,
     transition_function: TransitionFunction,
@@ -34,6 +42,10 @@ impl Machine {
         }
     }
 
+    pub fn set_transition_function(&mut self, transition_function: TransitionFunction) {
+        self.transition_function = transition_function;
+    }
+
     pub fn tick(&mut self, scanned_symbol: Symbol) -> Result<(Write, Action), String> {
         if self.state.is_halted() {
             return Ok((Write::None, Action::N));
@@ -61,6 +73,7 @@ impl Display for Write {
         match &self {
             Write::Print(s) => write!(f, "W({s})"),
             Write::Erase => write!(f, "E"),
+            Write::Delete => write!(f, "D"),
             Write::None => write!(f, "N"),
         }
     }
@@ -125,6 +138,7 @@ mod tests {
     #[test]
     fn print_write_write() {
         assert_eq!(Write::Erase.to_string(), String::from("E"));
+        assert_eq!(Write::Delete.to_string(), String::from("D"));
         assert_eq!(Write::None.to_string(), String::from("N"));
         assert_eq!(
             Write::Print(Symbol::from(10)).to_string(),
@@ -1,22 +1,34 @@
 use std::fmt::Display;
+use std::str::FromStr;
 
-use self::function::TransitionFunction;
+use self::function::{Input, TransitionFunction};
 use self::machine::{Action, Machine, State, Write};
-use self::tape::Tape;
+use self::stats::Stats;
+use self::tape::{Tape, TapeMode};
 
+pub mod check;
+pub mod equivalence;
 pub mod function;
 pub mod machine;
+#[cfg(feature = "serde")]
+pub(crate) mod pairs;
+pub mod stats;
 pub mod tape;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Symbol(Option<usize>);
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Universe {
     pub tape: Tape,
     pub pos: isize,
     pub machine: Machine,
     pub ticks: usize,
+    pub tape_mode: TapeMode,
+    pub stats: Stats,
 }
 
 impl Universe {
@@ -31,15 +43,19 @@ impl Universe {
             pos: initial_pos as isize,
             machine: Machine::new(initial_state, transition_function),
             ticks: 0,
+            tape_mode: TapeMode::default(),
+            stats: Stats::default(),
         }
     }
 
-    fn shift(&mut self, action: Action) {
-        match action {
-            Action::L => self.pos -= 1,
-            Action::R => self.pos += 1,
-            Action::N => {}
-        }
+    fn shift(&mut self, action: Action) -> Result<(), String> {
+        let to = match action {
+            Action::L => self.pos - 1,
+            Action::R => self.pos + 1,
+            Action::N => self.pos,
+        };
+        self.pos = self.tape_mode.step(self.pos, to)?;
+        Ok(())
     }
 
     pub fn tick(&mut self) -> Result<(Write, Action), String> {
@@ -48,11 +64,20 @@ impl Universe {
         }
 
         let scanned_symbol = self.tape.read(self.pos);
+        let input = Input {
+            state: self.machine.state,
+            symbol: scanned_symbol,
+        };
 
         let (print, action) = self.machine.tick(scanned_symbol)?;
 
+        let from = self.pos;
         self.tape.write(print, self.pos);
-        self.shift(action);
+        let shifted = self.shift(action);
+        if !input.state.is_halted() {
+            self.stats.record(input, action, from, self.pos);
+        }
+        shifted?;
 
         Ok((print, action))
     }
@@ -65,6 +90,11 @@ impl From<usize> for Symbol {
 }
 
 impl Symbol {
+    /// Same as [From], but usable in constants.
+    pub const fn new(value: usize) -> Self {
+        Symbol(Some(value))
+    }
+
     pub fn empty() -> Self {
         Symbol(None)
     }
@@ -83,8 +113,27 @@ impl Display for Symbol {
     }
 }
 
+impl FromStr for Symbol {
+    type Err = String;
+
+    /// Parse a symbol as shown by [Display], so `_` is the empty symbol.
+    fn from_str(symbol: &str) -> Result<Self, Self::Err> {
+        match symbol {
+            "_" => Ok(Symbol::empty()),
+            symbol => symbol
+                .parse::<usize>()
+                .map(Symbol::from)
+                .map_err(|_| format!("invalid symbol: {symbol}")),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use test_case::test_case;
+
+    use crate::presets::busy_beaver::two_state_busy_beaver;
+    use crate::universe::tape::{Edge, TapeMode};
     use crate::universe::Symbol;
 
     #[test]
@@ -101,4 +150,118 @@ mod tests {
     fn symbol_to_string_is_number() {
         assert_eq!(Symbol::from(4).to_string(), String::from("4"))
     }
+
+    #[test]
+    fn parse_symbol() {
+        assert_eq!("_".parse::<Symbol>(), Ok(Symbol::empty()));
+        assert_eq!("12".parse::<Symbol>(), Ok(Symbol::from(12)));
+        assert!("a".parse::<Symbol>().is_err());
+    }
+
+    #[test]
+    fn circular_tape_wraps() {
+        let mut universe = two_state_busy_beaver().universe;
+        universe.pos = 1;
+        universe.tape_mode = TapeMode::Circular { left: 1, right: 2 };
+
+        while !universe.machine.state.is_halted() {
+            universe.tick().unwrap();
+            assert!((1..=2).contains(&universe.pos));
+        }
+        assert_eq!(universe.tape.symbols(1..3), vec![Symbol::from(1); 2]);
+    }
+
+    #[test]
+    fn bounded_tape_fails_at_the_edge() {
+        let mut universe = two_state_busy_beaver().universe;
+        universe.pos = 1;
+        universe.tape_mode = TapeMode::Bounded {
+            left: 1,
+            right: 1,
+            edge: Edge::Error,
+        };
+
+        assert!(universe.tick().is_err());
+        assert_eq!(universe.pos, 1);
+    }
+
+    #[test_case(Edge::Stay, Ok(1))]
+    #[test_case(Edge::Error, Err(()))]
+    fn one_way_tape_left_edge(edge: Edge, expected: Result<isize, ()>) {
+        let mut universe = two_state_busy_beaver().universe;
+        universe.pos = 1;
+        universe.tape_mode = TapeMode::OneWay { left: 1, edge };
+
+        // A moves right, B moves left, and A then moves left from the left end
+        universe.tick().unwrap();
+        universe.tick().unwrap();
+        let result = universe.tick().map(|_| universe.pos).map_err(|_| ());
+
+        assert_eq!(result, expected);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_shape() {
+        use serde_json::json;
+
+        use crate::universe::function::{Input, Output};
+        use crate::universe::machine::{Action, State, Write};
+        use crate::universe::tape::Tape;
+
+        let input = Input {
+            state: State::halt(),
+            symbol: Symbol::empty(),
+        };
+        let output = Output {
+            write: Write::Print(Symbol::from(2)),
+            action: Action::L,
+            state: State::from(1),
+        };
+        let tape = Tape::from_iter([Symbol::from(1), Symbol::empty(), Symbol::from(2)]);
+
+        assert_eq!(
+            serde_json::to_value(input).unwrap(),
+            json!({ "state": null, "symbol": null })
+        );
+        assert_eq!(
+            serde_json::to_value(output).unwrap(),
+            json!({ "write": { "Print": 2 }, "action": "L", "state": 1 })
+        );
+        assert_eq!(serde_json::to_value(Write::Erase).unwrap(), json!("Erase"));
+        assert_eq!(
+            serde_json::to_value(TapeMode::OneWay {
+                left: 1,
+                edge: Edge::Stay
+            })
+            .unwrap(),
+            json!({ "OneWay": { "left": 1, "edge": "Stay" } })
+        );
+        assert_eq!(
+            serde_json::to_value(tape).unwrap(),
+            json!({ "start": 1, "symbols": [1, null, 2] })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn universe_round_trips_through_json() {
+        use crate::universe::Universe;
+
+        let mut universe = two_state_busy_beaver().universe;
+        universe.tick().unwrap();
+        universe.tick().unwrap();
+
+        let json = serde_json::to_string(&universe).unwrap();
+        let mut resumed: Universe = serde_json::from_str(&json).unwrap();
+        assert_eq!(resumed, universe);
+
+        while !universe.machine.state.is_halted() {
+            universe.tick().unwrap();
+            resumed.tick().unwrap();
+        }
+        assert_eq!(resumed.machine.state, universe.machine.state);
+        assert_eq!(resumed.pos, universe.pos);
+        assert_eq!(resumed.stats, universe.stats);
+    }
 }
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use super::function::Input;
+use super::machine::{Action, State};
+
+/// Statistics of a run, collected on every tick.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stats {
+    /// Number of ticks spent in each state, which follows from the transitions taken
+    #[cfg_attr(feature = "serde", serde(with = "super::pairs"))]
+    pub state_visits: HashMap<State, usize>,
+    /// Number of times the transition of each input was taken
+    #[cfg_attr(feature = "serde", serde(with = "super::pairs"))]
+    pub transition_fires: HashMap<Input, usize>,
+    /// Leftmost position of the head, if it ticked at all
+    pub leftmost_head: Option<isize>,
+    /// Rightmost position of the head, if it ticked at all
+    pub rightmost_head: Option<isize>,
+    /// Number of times the head moved in the opposite direction of its previous move
+    pub reversals: usize,
+    /// Direction of the last move of the head
+    pub last_move: Option<Action>,
+}
+
+impl Stats {
+    /// Record a transition taken from the input, after which the head moved from one position to the other.
+    pub fn record(&mut self, input: Input, action: Action, from: isize, to: isize) {
+        *self.state_visits.entry(input.state).or_default() += 1;
+        *self.transition_fires.entry(input).or_default() += 1;
+
+        for pos in [from, to] {
+            self.leftmost_head = Some(self.leftmost_head.map_or(pos, |left| left.min(pos)));
+            self.rightmost_head = Some(self.rightmost_head.map_or(pos, |right| right.max(pos)));
+        }
+
+        if action != Action::N {
+            if self.last_move.is_some_and(|last| last != action) {
+                self.reversals += 1;
+            }
+            self.last_move = Some(action);
+        }
+    }
+
+    /// Number of cells the head visited.
+    pub fn span(&self) -> usize {
+        match self.leftmost_head.zip(self.rightmost_head) {
+            Some((left, right)) => (right - left + 1) as usize,
+            None => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::presets::busy_beaver::{three_state_busy_beaver, two_state_busy_beaver};
+    use crate::universe::function::Input;
+    use crate::universe::machine::State;
+    use crate::universe::{Symbol, Universe};
+
+    fn run(mut universe: Universe) -> Universe {
+        while !universe.machine.state.is_halted() {
+            universe.tick().unwrap();
+        }
+        universe
+    }
+
+    #[test]
+    fn beaver_2_stats() {
+        let universe = run(two_state_busy_beaver().universe);
+        let stats = &universe.stats;
+        let (a, b) = (State::from(0), State::from(1));
+        let (se, s1) = (Symbol::empty(), Symbol::from(1));
+
+        assert_eq!(stats.state_visits[&a], 3);
+        assert_eq!(stats.state_visits[&b], 3);
+        assert_eq!(
+            stats.transition_fires[&Input {
+                state: a,
+                symbol: se
+            }],
+            2
+        );
+        assert_eq!(
+            stats.transition_fires[&Input {
+                state: b,
+                symbol: s1
+            }],
+            1
+        );
+        assert_eq!(
+            stats.transition_fires.values().sum::<usize>(),
+            universe.ticks
+        );
+        assert_eq!(
+            (stats.leftmost_head, stats.rightmost_head),
+            (Some(0), Some(3))
+        );
+        assert_eq!(stats.span(), 4);
+        // R L L L R R
+        assert_eq!(stats.reversals, 2);
+        assert_eq!(universe.tape.non_empty(), 4);
+    }
+
+    #[test]
+    fn beaver_3_visits_every_state() {
+        let universe = run(three_state_busy_beaver().universe);
+        let visits: usize = universe.stats.state_visits.values().sum();
+
+        assert_eq!(visits, universe.ticks);
+        assert_eq!(universe.stats.state_visits.len(), 3);
+        assert_eq!(universe.tape.non_empty(), 6);
+    }
+
+    #[test]
+    fn no_stats_before_ticking() {
+        let universe = two_state_busy_beaver().universe;
+
+        assert_eq!(universe.stats.span(), 0);
+        assert_eq!(universe.stats.reversals, 0);
+    }
+}
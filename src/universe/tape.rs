@@ -1,13 +1,52 @@
+use std::collections::{HashMap, VecDeque};
 use std::fmt::Display;
 use std::ops::Range;
 
+use clap::ValueEnum;
+
 use super::machine::Write;
 use super::Symbol;
 
+/// Which cells the head may move to, where only the cells the head can reach are shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TapeMode {
+    /// Infinite in both directions
+    #[default]
+    Unbounded,
+    /// Cells from `left` up to and including `right`, with end markers past which the head can not move
+    Bounded {
+        left: isize,
+        right: isize,
+        edge: Edge,
+    },
+    /// Cells from `left` up to and including `right`, where moving past one end continues at the other
+    Circular { left: isize, right: isize },
+    /// Cells from `left` onwards, which is infinite to the right only
+    OneWay { left: isize, edge: Edge },
+}
+
+/// What happens when the head moves past an end of the tape.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Edge {
+    /// The machine fails
+    #[default]
+    Error,
+    /// The head stays at the end
+    Stay,
+}
+
 #[derive(Debug, Clone, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "Cells", from = "Cells"))]
 pub struct Tape {
-    positive: Vec<Symbol>,
-    negative: Vec<Symbol>,
+    /// Cells from the leftmost written one up to and including the rightmost written one
+    cells: VecDeque<Symbol>,
+    /// Position of the first of the cells
+    start: isize,
+    /// Positions of the leftmost and rightmost cells which are not empty, kept up to date on every write
+    non_empty_bounds: Option<(isize, isize)>,
 }
 
 impl PartialEq for Tape {
@@ -28,117 +67,272 @@ impl PartialEq for Tape {
     }
 }
 
+/// Symbols of a tape displayed with a label for some of them.
+pub struct LabelledTape<'a> {
+    symbols: Vec<Symbol>,
+    labels: &'a HashMap<Symbol, String>,
+}
+
 impl Display for Tape {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            self.all_symbols()
-                .iter()
-                .map(|i| i.to_string())
-                .collect::<String>()
-        )
+        write!(f, "{}", self.labelled(&HashMap::new()))
+    }
+}
+
+impl Display for LabelledTape<'_> {
+    /// Symbols are separated by spaces if any label is longer than a single character.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let separator = if self.labels.values().any(|l| l.chars().count() > 1) {
+            " "
+        } else {
+            ""
+        };
+        let symbols: Vec<String> = self
+            .symbols
+            .iter()
+            .map(|s| self.labels.get(s).cloned().unwrap_or_else(|| s.to_string()))
+            .collect();
+
+        write!(f, "{}", symbols.join(separator))
+    }
+}
+
+impl TapeMode {
+    /// Leftmost and rightmost cell the head can reach, if any.
+    pub fn bounds(&self) -> (Option<isize>, Option<isize>) {
+        match *self {
+            TapeMode::Unbounded => (None, None),
+            TapeMode::Bounded { left, right, .. } | TapeMode::Circular { left, right } => {
+                (Some(left), Some(right))
+            }
+            TapeMode::OneWay { left, .. } => (Some(left), None),
+        }
+    }
+
+    /// Position of the head after moving from one cell to the next.
+    pub fn step(&self, from: isize, to: isize) -> Result<isize, String> {
+        let edge = match *self {
+            TapeMode::Unbounded => return Ok(to),
+            TapeMode::Circular { left, right } => {
+                return Ok((to - left).rem_euclid(right - left + 1) + left)
+            }
+            TapeMode::Bounded { edge, .. } | TapeMode::OneWay { edge, .. } => edge,
+        };
+
+        let (left, right) = self.bounds();
+        if left.is_some_and(|left| to < left) {
+            match edge {
+                Edge::Error => Err(format!("moved past the left end of the tape at {from}")),
+                Edge::Stay => Ok(from),
+            }
+        } else if right.is_some_and(|right| to > right) {
+            match edge {
+                Edge::Error => Err(format!("moved past the right end of the tape at {from}")),
+                Edge::Stay => Ok(from),
+            }
+        } else {
+            Ok(to)
+        }
+    }
+}
+
+/// Written cells of a tape, the first of which is at `start`, as a tape is serialized.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Cells {
+    start: isize,
+    symbols: Vec<Symbol>,
+}
+
+#[cfg(feature = "serde")]
+impl From<Tape> for Cells {
+    fn from(tape: Tape) -> Self {
+        match tape.leftmost_written().zip(tape.rightmost_written()) {
+            Some((leftmost, rightmost)) => Cells {
+                start: leftmost,
+                symbols: tape.symbols(leftmost..rightmost + 1),
+            },
+            None => Cells {
+                start: 0,
+                symbols: vec![],
+            },
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Cells> for Tape {
+    fn from(cells: Cells) -> Self {
+        let mut tape = Tape::default();
+        for (i, symbol) in cells.symbols.into_iter().enumerate() {
+            tape.write(Write::Print(symbol), cells.start + i as isize);
+        }
+        tape
     }
 }
 
 impl FromIterator<Symbol> for Tape {
     fn from_iter<T: IntoIterator<Item = Symbol>>(symbols: T) -> Self {
-        Tape {
-            positive: Vec::from_iter(symbols),
-            ..Default::default()
+        let mut tape = Tape {
+            cells: VecDeque::from_iter(symbols),
+            start: 1,
+            non_empty_bounds: None,
+        };
+        if let Some(rightmost) = tape.rightmost_written() {
+            tape.non_empty_bounds = tape.shrink(1, rightmost);
         }
+        tape
     }
 }
 
 impl Tape {
-    pub fn read(&self, pos: isize) -> Symbol {
-        if pos.is_positive() {
-            self.positive.get(pos as usize - 1)
-        } else {
-            self.negative.get(pos.unsigned_abs())
+    pub fn labelled<'a>(&self, labels: &'a HashMap<Symbol, String>) -> LabelledTape<'a> {
+        LabelledTape {
+            symbols: self.all_symbols(),
+            labels,
         }
-        .cloned()
-        .unwrap_or_else(Symbol::empty)
     }
 
-    pub fn write(&mut self, write: Write, pos: isize) {
-        let (tape_half, index) = if pos.is_positive() {
-            (&mut self.positive, pos as usize - 1)
-        } else {
-            (&mut self.negative, pos.unsigned_abs())
-        };
+    /// Cells in the range, displayed with the given labels.
+    pub fn labelled_range<'a>(
+        &self,
+        range: Range<isize>,
+        labels: &'a HashMap<Symbol, String>,
+    ) -> LabelledTape<'a> {
+        LabelledTape {
+            symbols: self.symbols(range),
+            labels,
+        }
+    }
+
+    pub fn read(&self, pos: isize) -> Symbol {
+        usize::try_from(pos - self.start)
+            .ok()
+            .and_then(|index| self.cells.get(index))
+            .cloned()
+            .unwrap_or_else(Symbol::empty)
+    }
 
+    /// Write to the cell at the given position, where erasing leaves an empty cell behind.
+    ///
+    /// Deleting removes the cell, after which every cell to its right moves one position to the left.
+    pub fn write(&mut self, write: Write, pos: isize) {
         match write {
             Write::Print(symbol) => {
-                let append_len = (index + 1).saturating_sub(tape_half.len());
-                tape_half.append(&mut vec![Symbol::default(); append_len]);
-                tape_half[index] = symbol;
+                if self.cells.is_empty() {
+                    self.start = pos;
+                }
+                while pos < self.start {
+                    self.cells.push_front(Symbol::empty());
+                    self.start -= 1;
+                }
+                let index = (pos - self.start) as usize;
+                if index >= self.cells.len() {
+                    self.cells.resize(index + 1, Symbol::empty());
+                }
+                self.cells[index] = symbol;
             }
-            Write::Erase if index < tape_half.len() => {
-                tape_half.remove(index);
+            Write::Erase => {
+                if let Some(cell) = self.cell_mut(pos) {
+                    *cell = Symbol::empty();
+                }
             }
-            _ => {}
+            Write::Delete if self.cells.is_empty() => {}
+            Write::Delete if pos < self.start => self.start -= 1,
+            Write::Delete => {
+                self.cells.remove((pos - self.start) as usize);
+            }
+            Write::None => {}
         }
+
+        self.non_empty_bounds = match (write, self.non_empty_bounds) {
+            (Write::None, bounds) => bounds,
+            (Write::Delete, Some((left, right))) if pos < left => Some((left - 1, right - 1)),
+            (Write::Delete, Some((left, right))) if pos <= right => self.shrink(left, right - 1),
+            (Write::Delete, bounds) => bounds,
+            (_, bounds) if !self.read(pos).is_empty() => match bounds {
+                Some((left, right)) => Some((left.min(pos), right.max(pos))),
+                None => Some((pos, pos)),
+            },
+            (_, Some((left, right))) if pos == left || pos == right => self.shrink(left, right),
+            (_, bounds) => bounds,
+        };
     }
 
-    pub fn all_symbols(&self) -> Vec<Symbol> {
-        if self.positive.is_empty() && self.negative.is_empty() {
-            return Vec::default();
+    /// Bounds of the non-empty cells from `left` up to and including `right`, looking only at the empty cells at both ends.
+    fn shrink(&self, mut left: isize, mut right: isize) -> Option<(isize, isize)> {
+        while left <= right && self.read(left).is_empty() {
+            left += 1;
         }
+        while left < right && self.read(right).is_empty() {
+            right -= 1;
+        }
+        (left <= right).then_some((left, right))
+    }
 
-        let mut negative: Vec<_> = if self.negative.is_empty() {
-            vec![Symbol::empty()]
-        } else {
-            self.negative.iter().rev().cloned().collect()
-        };
+    fn cell_mut(&mut self, pos: isize) -> Option<&mut Symbol> {
+        let index = usize::try_from(pos - self.start).ok()?;
+        self.cells.get_mut(index)
+    }
 
-        negative.append(&mut self.positive.clone());
-        negative
+    /// Symbols from the leftmost written cell to the rightmost written cell.
+    pub fn all_symbols(&self) -> Vec<Symbol> {
+        self.cells.iter().cloned().collect()
     }
 
+    /// Symbols of exactly the cells in the range, where cells which were never written are empty.
     pub fn symbols(&self, range: Range<isize>) -> Vec<Symbol> {
-        if self.positive.is_empty() && self.negative.is_empty() {
-            return Vec::default();
-        }
-
-        let neg_range = 0..range.start.min(0).unsigned_abs().min(self.negative.len());
-        let pos_range = 0..range.end.max(0).unsigned_abs().min(self.positive.len());
+        range.map(|pos| self.read(pos)).collect()
+    }
 
-        let mut symbols: Vec<_> = if self.negative.is_empty() {
-            vec![Symbol::empty()]
-        } else {
-            self.negative[neg_range].iter().rev().cloned().collect()
-        };
+    /// Position of the leftmost cell which was written, every cell to its left is empty.
+    pub fn leftmost_written(&self) -> Option<isize> {
+        (!self.cells.is_empty()).then_some(self.start)
+    }
 
-        symbols.append(&mut self.positive[pos_range].to_vec());
-        symbols
+    /// Position of the rightmost cell which was written, every cell to its right is empty.
+    pub fn rightmost_written(&self) -> Option<isize> {
+        (!self.cells.is_empty()).then(|| self.start + self.cells.len() as isize - 1)
     }
 
-    pub fn first_half(&self) -> Vec<Symbol> {
-        if self.negative.is_empty() {
-            vec![Symbol::empty()]
-        } else {
-            self.negative.clone()
-        }
+    /// Positions of the leftmost and rightmost cells which are not empty, if any.
+    pub fn non_empty_bounds(&self) -> Option<(isize, isize)> {
+        self.non_empty_bounds
     }
 
-    pub fn second_half(&self) -> Vec<Symbol> {
-        self.positive.clone()
+    /// Number of cells which are not empty.
+    pub fn non_empty(&self) -> usize {
+        self.cells.iter().filter(|s| !s.is_empty()).count()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.positive.is_empty() && self.negative.is_empty()
-            || self.all_symbols().iter().all(|s| s.is_empty())
+        self.cells.iter().all(|s| s.is_empty())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use crate::universe::machine::Write;
     use crate::universe::Symbol;
+    use proptest::prelude::*;
     use test_case::test_case;
 
-    use super::Tape;
+    use super::{Edge, Tape, TapeMode};
+
+    /// Tape with the given symbols written at the given positions.
+    fn tape_from_writes(writes: &[(isize, usize)]) -> Tape {
+        let mut tape = Tape::default();
+        for (pos, symbol) in writes {
+            tape.write(Write::Print(Symbol::from(*symbol)), *pos);
+        }
+        tape
+    }
+
+    fn writes() -> impl Strategy<Value = Vec<(isize, usize)>> {
+        prop::collection::vec((-20_isize..20, 0_usize..4), 0..20)
+    }
 
     #[test]
     fn empty_symbol_is_default() {
@@ -186,7 +380,7 @@ mod tests {
         tape.write(Write::Print(Symbol::from(5)), -1);
         tape.write(Write::Print(Symbol::from(6)), -2);
 
-        assert_eq!(tape.to_string(), String::from("465_"));
+        assert_eq!(tape.to_string(), String::from("465"));
     }
 
     #[test]
@@ -206,7 +400,7 @@ mod tests {
         tape.write(Write::Print(Symbol::from(5)), 1);
         tape.write(Write::Print(Symbol::from(6)), 2);
 
-        assert_eq!(tape.to_string(), String::from("_564"));
+        assert_eq!(tape.to_string(), String::from("564"));
     }
 
     #[test]
@@ -225,8 +419,8 @@ mod tests {
     }
 
     #[test_case(0, "3")]
-    #[test_case(1, "_3")]
-    #[test_case(8, "________3")]
+    #[test_case(1, "3")]
+    #[test_case(8, "3")]
     fn write_to_empty_tape_positive(index: isize, expected: &str) {
         let mut tape = Tape::default();
         tape.write(Write::Print(Symbol::from(3)), index);
@@ -234,7 +428,7 @@ mod tests {
         assert_eq!(tape.to_string(), expected.to_string());
     }
 
-    #[test_case(-1, "3_")]
+    #[test_case(-1, "3")]
     fn write_to_empty_tape_negative(index: isize, expected: &str) {
         let mut tape = Tape::default();
         tape.write(Write::Print(Symbol::from(3)), index);
@@ -262,4 +456,173 @@ mod tests {
     fn tape_only_empty_symbol_is_empty() {
         assert!(Tape::from_iter([Symbol::empty(), Symbol::empty()]).is_empty());
     }
+
+    #[test_case(&[(1, "a"), (2, "b")], "ab_a")]
+    #[test_case(&[(1, "a"), (2, "bb")], "a bb _ a")]
+    #[test_case(&[(2, "b")], "1b_1")]
+    fn labelled_tape(labels: &[(usize, &str)], expected: &str) {
+        let (s1, s2) = (Symbol::from(1), Symbol::from(2));
+        let tape = Tape::from_iter([s1, s2, Symbol::empty(), s1]);
+        let labels: HashMap<Symbol, String> = labels
+            .iter()
+            .map(|(symbol, label)| (Symbol::from(*symbol), label.to_string()))
+            .collect();
+
+        assert_eq!(tape.labelled(&labels).to_string(), expected);
+    }
+
+    #[test_case(-2, "1_345")]
+    #[test_case(0, "123_5")]
+    #[test_case(1, "1234_")]
+    #[test_case(5, "12345")]
+    fn erase_leaves_empty_cell(pos: isize, expected: &str) {
+        let mut tape = tape_from_writes(&[(-3, 1), (-2, 2), (-1, 3), (0, 4), (1, 5)]);
+        tape.write(Write::Erase, pos);
+
+        assert_eq!(tape.to_string(), expected);
+    }
+
+    #[test_case(-2, "1345")]
+    #[test_case(0, "1235")]
+    #[test_case(1, "1234")]
+    #[test_case(5, "12345")]
+    fn delete_shifts_cells_to_the_left(pos: isize, expected: &str) {
+        let mut tape = tape_from_writes(&[(-3, 1), (-2, 2), (-1, 3), (0, 4), (1, 5)]);
+        tape.write(Write::Delete, pos);
+
+        assert_eq!(tape.to_string(), expected);
+    }
+
+    #[test_case(&[], None, None)]
+    #[test_case(&[(0, 1)], Some(0), Some(0))]
+    #[test_case(&[(3, 1)], Some(3), Some(3))]
+    #[test_case(&[(-2, 1)], Some(-2), Some(-2))]
+    #[test_case(&[(-2, 1), (4, 2)], Some(-2), Some(4))]
+    fn written_bounds(
+        writes: &[(isize, usize)],
+        leftmost: Option<isize>,
+        rightmost: Option<isize>,
+    ) {
+        let tape = tape_from_writes(writes);
+
+        assert_eq!(tape.leftmost_written(), leftmost);
+        assert_eq!(tape.rightmost_written(), rightmost);
+    }
+
+    #[test_case(&[], None)]
+    #[test_case(&[(3, 0)], Some((3, 3)))]
+    #[test_case(&[(-2, 1), (4, 2)], Some((-2, 4)))]
+    fn non_empty_bounds(writes: &[(isize, usize)], expected: Option<(isize, isize)>) {
+        let mut tape = tape_from_writes(writes);
+        tape.write(Write::Erase, 5);
+        tape.write(Write::Print(Symbol::empty()), 7);
+
+        assert_eq!(tape.non_empty_bounds(), expected);
+    }
+
+    #[test_case(-2..3, "____5")]
+    #[test_case(1..2, "_")]
+    #[test_case(2..5, "5__")]
+    #[test_case(3..3, "")]
+    fn symbols_of_range(range: std::ops::Range<isize>, expected: &str) {
+        let tape = tape_from_writes(&[(2, 5)]);
+        let expected: Vec<Symbol> = expected
+            .chars()
+            .map(|c| c.to_string().parse().unwrap())
+            .collect();
+
+        assert_eq!(tape.symbols(range), expected);
+    }
+
+    #[test_case(TapeMode::Unbounded, 5, 6, Ok(6))]
+    #[test_case(TapeMode::Bounded { left: 1, right: 5, edge: Edge::Stay }, 4, 5, Ok(5))]
+    #[test_case(TapeMode::Bounded { left: 1, right: 5, edge: Edge::Stay }, 5, 6, Ok(5))]
+    #[test_case(TapeMode::Bounded { left: 1, right: 5, edge: Edge::Stay }, 1, 0, Ok(1))]
+    #[test_case(TapeMode::Bounded { left: 1, right: 5, edge: Edge::Error }, 5, 6, Err(()))]
+    #[test_case(TapeMode::Bounded { left: 1, right: 5, edge: Edge::Error }, 1, 0, Err(()))]
+    #[test_case(TapeMode::Circular { left: 1, right: 5 }, 5, 6, Ok(1))]
+    #[test_case(TapeMode::Circular { left: 1, right: 5 }, 1, 0, Ok(5))]
+    #[test_case(TapeMode::Circular { left: -1, right: 1 }, 0, 1, Ok(1))]
+    #[test_case(TapeMode::OneWay { left: 0, edge: Edge::Stay }, 0, -1, Ok(0))]
+    #[test_case(TapeMode::OneWay { left: 0, edge: Edge::Error }, 0, -1, Err(()))]
+    #[test_case(TapeMode::OneWay { left: 0, edge: Edge::Error }, 1000, 1001, Ok(1001))]
+    fn step(tape_mode: TapeMode, from: isize, to: isize, expected: Result<isize, ()>) {
+        assert_eq!(tape_mode.step(from, to).map_err(|_| ()), expected);
+    }
+
+    proptest! {
+        #[test]
+        fn symbols_are_exactly_the_range(writes in writes(), start in -25_isize..25, len in 0_isize..10) {
+            let tape = tape_from_writes(&writes);
+            let symbols = tape.symbols(start..start + len);
+
+            prop_assert_eq!(symbols.len(), len as usize);
+            for (i, symbol) in symbols.into_iter().enumerate() {
+                prop_assert_eq!(symbol, tape.read(start + i as isize));
+            }
+        }
+
+        #[test]
+        fn erase_only_empties_its_cell(writes in writes(), pos in -25_isize..25) {
+            let before = tape_from_writes(&writes);
+            let mut tape = before.clone();
+            tape.write(Write::Erase, pos);
+
+            for p in -25..25 {
+                let expected = if p == pos { Symbol::empty() } else { before.read(p) };
+                prop_assert_eq!(tape.read(p), expected);
+            }
+        }
+
+        #[test]
+        fn delete_moves_cells_to_its_right(writes in writes(), pos in -25_isize..25) {
+            let before = tape_from_writes(&writes);
+            let mut tape = before.clone();
+            tape.write(Write::Delete, pos);
+
+            for p in -25..25 {
+                let expected = if p < pos { before.read(p) } else { before.read(p + 1) };
+                prop_assert_eq!(tape.read(p), expected);
+            }
+        }
+
+        #[test]
+        fn non_empty_bounds_follow_every_write(writes in writes(), changes in prop::collection::vec((-25_isize..25, 0_usize..4), 0..20)) {
+            let mut tape = tape_from_writes(&writes);
+            for (pos, change) in changes {
+                let write = match change {
+                    0 => Write::Erase,
+                    1 => Write::Delete,
+                    2 => Write::Print(Symbol::empty()),
+                    _ => Write::Print(Symbol::from(pos.unsigned_abs())),
+                };
+                tape.write(write, pos);
+
+                let non_empty: Vec<_> = (-50..50).filter(|p| !tape.read(*p).is_empty()).collect();
+                let expected = non_empty.first().copied().zip(non_empty.last().copied());
+                prop_assert_eq!(tape.non_empty_bounds(), expected);
+            }
+        }
+
+        #[test]
+        fn write_none_changes_nothing(writes in writes(), pos in -25_isize..25) {
+            let before = tape_from_writes(&writes);
+            let mut tape = before.clone();
+            tape.write(Write::None, pos);
+
+            prop_assert_eq!(tape.all_symbols(), before.all_symbols());
+        }
+
+        #[test]
+        fn print_then_read(writes in writes(), pos in -25_isize..25, symbol in 0_usize..4) {
+            let before = tape_from_writes(&writes);
+            let mut tape = before.clone();
+            tape.write(Write::Print(Symbol::from(symbol)), pos);
+
+            for p in -25..25 {
+                let expected = if p == pos { Symbol::from(symbol) } else { before.read(p) };
+                prop_assert_eq!(tape.read(p), expected);
+            }
+        }
+    }
 }